@@ -16,6 +16,44 @@ enum Token<'a> {
     Ident(&'a str),
 }
 
+#[derive(Debug, Lexer, PartialEq, Eq)]
+#[lexer(codegen = "native", skip = "//[^\n]*", skip = r"[ \t\r\n\f]+")]
+enum TokenNative<'a> {
+    #[token("import")]
+    Import,
+    #[token(";")]
+    Semi,
+    #[regex("[a-zA-Z_][a-zA-Z0-9_]*")]
+    Ident(&'a str),
+}
+
+#[derive(Debug, Lexer, PartialEq, Eq)]
+#[lexer(accelerate, skip = "//[^\n]*", skip = r"[ \t\r\n\f]+")]
+enum TokenAccelerated<'a> {
+    #[token("import")]
+    Import,
+    #[token(";")]
+    Semi,
+    #[regex("[a-zA-Z_][a-zA-Z0-9_]*")]
+    Ident(&'a str),
+}
+
+#[derive(Debug, Lexer, PartialEq, Eq)]
+#[lexer(
+    accelerate,
+    codegen = "native",
+    skip = "//[^\n]*",
+    skip = r"[ \t\r\n\f]+"
+)]
+enum TokenNativeAccelerated<'a> {
+    #[token("import")]
+    Import,
+    #[token(";")]
+    Semi,
+    #[regex("[a-zA-Z_][a-zA-Z0-9_]*")]
+    Ident(&'a str),
+}
+
 #[derive(Debug, Logos, PartialEq, Eq)]
 #[logos(skip "//[^\n]*", skip r"[ \t\r\n\f]+")]
 enum TokenLogos<'a> {
@@ -33,6 +71,50 @@ import something_else;
 import something_else1;
 "####;
 
+const COMMENT_HEAVY_INPUT: &str = r####"import import1;
+// This line is a long comment that the lexer has to step through byte by byte
+// unless it is accelerated: lorem ipsum dolor sit amet, consectetur adipiscing
+// elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua. ✓✓
+                // Indented comment after a long run of whitespace...............
+import something_else;
+                                                                        // ....
+import something_else1;
+"####;
+
+macro_rules! bench_lexer {
+    ($name:ident, $token:ident, $input:expr) => {
+        #[bench]
+        fn $name(b: &mut test::Bencher) {
+            let mut tokens = $token::lex($input);
+
+            b.bytes = $input.len() as u64;
+            b.iter(|| {
+                tokens.consumed = 0;
+                tokens.by_ref().count()
+            });
+        }
+    };
+}
+
+bench_lexer!(bench_comments, Token, COMMENT_HEAVY_INPUT);
+bench_lexer!(
+    bench_comments_accelerated,
+    TokenAccelerated,
+    COMMENT_HEAVY_INPUT
+);
+bench_lexer!(bench_comments_native, TokenNative, COMMENT_HEAVY_INPUT);
+bench_lexer!(
+    bench_comments_native_accelerated,
+    TokenNativeAccelerated,
+    COMMENT_HEAVY_INPUT
+);
+
+#[bench]
+fn bench_comments_logos(b: &mut test::Bencher) {
+    b.bytes = COMMENT_HEAVY_INPUT.len() as u64;
+    b.iter(|| TokenLogos::lexer(COMMENT_HEAVY_INPUT).by_ref().count());
+}
+
 #[bench]
 fn bench_lex(b: &mut test::Bencher) {
     let mut tokens = Token::lex(INPUT);
@@ -44,6 +126,17 @@ fn bench_lex(b: &mut test::Bencher) {
     });
 }
 
+#[bench]
+fn bench_lex_native(b: &mut test::Bencher) {
+    let mut tokens = TokenNative::lex(INPUT);
+
+    b.bytes = INPUT.len() as u64;
+    b.iter(|| {
+        tokens.consumed = 0;
+        tokens.by_ref().count()
+    });
+}
+
 #[bench]
 fn bench_logos(b: &mut test::Bencher) {
     b.bytes = INPUT.len() as u64;
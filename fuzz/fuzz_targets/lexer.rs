@@ -0,0 +1,30 @@
+//! A template fuzz target: replace `Token` with your lexer and run
+//! `cargo fuzz run lexer` in this directory.
+#![no_main]
+
+use lexi_matic::{more, Lexer};
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Debug, Lexer)]
+#[lexer(skip = r"[ \t\r\n\f]+", skip = "//[^\n]*")]
+enum Token {
+    #[token("fn")]
+    Fn,
+    #[token("(")]
+    LParen,
+    #[token(")")]
+    RParen,
+    #[regex("[a-zA-Z_][a-zA-Z0-9_]*")]
+    Ident,
+    #[regex("[0-9]+")]
+    Num,
+    #[regex(r#""([^"\\]|\\.)*""#)]
+    String,
+    #[regex(r##"b?r#*""##)]
+    #[lexer(more = more::rust_raw_string)]
+    RawString,
+}
+
+fuzz_target!(|input: &str| {
+    lexi_matic::testing::check_invariants::<Token>(input);
+});
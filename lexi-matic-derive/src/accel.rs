@@ -0,0 +1,106 @@
+//! Support for `#[lexer(accelerate)]`.
+use regex_automata::{
+    dfa::{dense::DFA, Automaton},
+    util::start::Config,
+    Anchored,
+};
+use regex_syntax::hir::{Capture, Class, ClassBytes, ClassBytesRange, Hir, HirKind, Repetition};
+
+/// Rewrite `C*` and `C+`, where `C` is a Unicode class matching every
+/// character except some ASCII ones, e.g. `[^\n]*`, to the byte class
+/// matching every byte except those ASCII ones.
+///
+/// The input is always valid UTF-8 and the rest of the pattern can only
+/// match whole characters, so this doesn't change what the pattern matches,
+/// but it turns the loop into a single DFA state that can be accelerated with
+/// memchr. The UTF-8 version has a transition for every leading byte.
+pub fn rewrite_negated_ascii(hir: Hir) -> Hir {
+    match hir.into_kind() {
+        HirKind::Repetition(r) if r.min <= 1 && r.max.is_none() => {
+            let sub = match negated_ascii(&r.sub) {
+                Some(bytes) => Hir::class(Class::Bytes(bytes)),
+                None => rewrite_negated_ascii(*r.sub),
+            };
+            Hir::repetition(Repetition {
+                sub: Box::new(sub),
+                ..r
+            })
+        }
+        HirKind::Repetition(r) => Hir::repetition(Repetition {
+            sub: Box::new(rewrite_negated_ascii(*r.sub)),
+            ..r
+        }),
+        HirKind::Capture(c) => Hir::capture(Capture {
+            sub: Box::new(rewrite_negated_ascii(*c.sub)),
+            ..c
+        }),
+        HirKind::Concat(subs) => Hir::concat(subs.into_iter().map(rewrite_negated_ascii).collect()),
+        HirKind::Alternation(subs) => {
+            Hir::alternation(subs.into_iter().map(rewrite_negated_ascii).collect())
+        }
+        HirKind::Empty => Hir::empty(),
+        HirKind::Literal(l) => Hir::literal(l.0),
+        HirKind::Class(c) => Hir::class(c),
+        HirKind::Look(l) => Hir::look(l),
+    }
+}
+
+/// If `hir` is a Unicode class whose complement is ASCII only, return the
+/// byte class matching every byte but that complement.
+fn negated_ascii(hir: &Hir) -> Option<ClassBytes> {
+    let HirKind::Class(Class::Unicode(c)) = hir.kind() else {
+        return None;
+    };
+    let mut complement = c.clone();
+    complement.negate();
+    if !complement.is_ascii() {
+        return None;
+    }
+    let mut bytes = complement.to_byte_class()?;
+    bytes.negate();
+    Some(bytes)
+}
+
+/// If the skip pattern `hir` is `[...]+` with a small ASCII class, return
+/// the bytes of the class.
+///
+/// The generated lexer can then skip whole runs of these bytes without
+/// running the DFA, provided that no other pattern (`others`) can start with
+/// them.
+pub fn skip_run_bytes(hir: &Hir, others: &DFA<Vec<u32>>) -> Option<Vec<u8>> {
+    let HirKind::Repetition(r) = hir.kind() else {
+        return None;
+    };
+    if r.min != 1 || r.max.is_some() {
+        return None;
+    }
+    let bytes: Vec<u8> = match r.sub.kind() {
+        HirKind::Class(Class::Unicode(c)) if c.is_ascii() => c
+            .to_byte_class()?
+            .ranges()
+            .iter()
+            .flat_map(|r| r.start()..=r.end())
+            .collect(),
+        HirKind::Class(Class::Bytes(c)) if c.is_ascii() => c
+            .ranges()
+            .iter()
+            .flat_map(|r: &ClassBytesRange| r.start()..=r.end())
+            .collect(),
+        _ => return None,
+    };
+    if bytes.is_empty() || bytes.len() > 6 {
+        return None;
+    }
+
+    let start = others
+        .start_state(&Config::new().anchored(Anchored::Yes))
+        .unwrap();
+    if bytes
+        .iter()
+        .all(|&b| others.is_dead_state(others.next_state(start, b)))
+    {
+        Some(bytes)
+    } else {
+        None
+    }
+}
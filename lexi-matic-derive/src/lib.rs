@@ -1,11 +1,20 @@
 extern crate proc_macro;
 use proc_macro::TokenStream;
-use quote::{format_ident, quote};
+use quote::{format_ident, quote, ToTokens};
 use regex_automata::{
     dfa::{dense::DFA, StartKind},
+    nfa::thompson,
     MatchKind,
 };
-use syn::{parse_macro_input, Data, DeriveInput, Ident, LitStr};
+use regex_syntax::hir::Hir;
+use syn::{
+    parse_macro_input, parse_quote, Data, DeriveInput, Fields, GenericParam, Ident, Lifetime,
+    LitStr, Type,
+};
+
+mod accel;
+mod native;
+mod textmate;
 
 /// Derive the Lexer implementation.
 #[proc_macro_derive(Lexer, attributes(regex, token, lexer))]
@@ -24,13 +33,39 @@ fn derive_lexer_impl(item: DeriveInput) -> syn::Result<proc_macro2::TokenStream>
     let vis = item.vis;
     let name = item.ident;
 
-    let mut skip_regexes = Vec::new();
+    let mut skips = Vec::new();
+    let mut native = false;
+    let mut accelerate = false;
+    let mut owned = false;
+    let mut interner: Option<Type> = None;
+    let mut error: Option<Type> = None;
     for a in item.attrs {
         if a.path().is_ident("lexer") {
             a.parse_nested_meta(|m| {
                 if m.path.is_ident("skip") {
-                    let r: LitStr = m.value()?.parse()?;
-                    skip_regexes.push(r.value());
+                    skips.push(parse_skip(&m, skips.len())?);
+                    Ok(())
+                } else if m.path.is_ident("codegen") {
+                    let c: LitStr = m.value()?.parse()?;
+                    native = match &*c.value() {
+                        "dfa" => false,
+                        "native" => true,
+                        _ => {
+                            return Err(syn::Error::new_spanned(c, "expect \"dfa\" or \"native\""))
+                        }
+                    };
+                    Ok(())
+                } else if m.path.is_ident("accelerate") {
+                    accelerate = true;
+                    Ok(())
+                } else if m.path.is_ident("owned") {
+                    owned = true;
+                    Ok(())
+                } else if m.path.is_ident("interner") {
+                    interner = Some(m.value()?.parse()?);
+                    Ok(())
+                } else if m.path.is_ident("error") {
+                    error = Some(m.value()?.parse()?);
                     Ok(())
                 } else {
                     Err(m.error("unsupported attribute"))
@@ -41,35 +76,132 @@ fn derive_lexer_impl(item: DeriveInput) -> syn::Result<proc_macro2::TokenStream>
 
     let mut regexes = Vec::with_capacity(e.variants.len());
     let mut matches = Vec::new();
-    for (i, v) in e.variants.iter().enumerate() {
+    // Type of `#[lexer(intern)]` fields.
+    let mut symbol: Option<Type> = None;
+    // The `#[lexer(eof)]` variant.
+    let mut eof: Option<&Ident> = None;
+    // `#[lexer(semantic = ...)]` of all variants.
+    let mut semantics = Vec::with_capacity(e.variants.len());
+    // Arguments of `pattern` for variants and skip patterns, only translated
+    // if any of them has a scope.
+    let mut patterns = Vec::new();
+    for v in &e.variants {
         let vn = &v.ident;
-        let i = i as u32;
-        let mut more: Option<Ident> = None;
+        let i = regexes.len() as u32;
+        let mut more: Option<syn::Path> = None;
+        let mut callback: Option<syn::Path> = None;
+        let mut is_eof = false;
+        let mut semantic: Option<LitStr> = None;
+        let mut scope: Option<LitStr> = None;
         for a in &v.attrs {
             if a.path().is_ident("lexer") {
                 a.parse_nested_meta(|m| {
                     if m.path.is_ident("more") {
                         more = Some(m.value()?.parse()?);
                         Ok(())
+                    } else if m.path.is_ident("callback") {
+                        callback = Some(m.value()?.parse()?);
+                        Ok(())
+                    } else if m.path.is_ident("eof") {
+                        is_eof = true;
+                        Ok(())
+                    } else if m.path.is_ident("semantic") {
+                        semantic = Some(m.value()?.parse()?);
+                        Ok(())
+                    } else if m.path.is_ident("scope") {
+                        scope = Some(m.value()?.parse()?);
+                        Ok(())
                     } else {
                         Err(m.error("unsupported attribute"))
                     }
                 })?;
             }
         }
-        let more = match more {
-            Some(more) => quote! {
-                len += match #more(&remaining[..len], &remaining[len..]) {
-                    Some(len) => len,
-                    None => return Some(Err(lexi_matic::Error(start))),
-                };
-            },
-            None => quote!(),
-        };
-        let construct = if v.fields.is_empty() {
-            quote!(#name::#vn)
-        } else {
-            quote!(#name::#vn((&remaining[..len]).into()))
+        semantics.push(semantic);
+        if is_eof {
+            if eof.is_some() {
+                return Err(syn::Error::new_spanned(v, "duplicated eof variant"));
+            }
+            let has_regex = v
+                .attrs
+                .iter()
+                .any(|a| a.path().is_ident("regex") || a.path().is_ident("token"));
+            if !v.fields.is_empty() || has_regex || more.is_some() || callback.is_some() {
+                return Err(syn::Error::new_spanned(
+                    v,
+                    "an eof variant can't have fields, a regex, token, more or callback",
+                ));
+            }
+            eof = Some(vn);
+            continue;
+        }
+        let more = extension(more, callback, v, &vn.to_string(), quote!(None))?;
+        let several = v.fields.len() > 1;
+        let mut values = Vec::with_capacity(v.fields.len());
+        for f in &v.fields {
+            let ty = &f.ty;
+            let mut value = None;
+            for a in &f.attrs {
+                if a.path().is_ident("lexer") {
+                    a.parse_nested_meta(|m| {
+                        let v = if m.path.is_ident("text") {
+                            quote!((&remaining[..len]).into())
+                        } else if m.path.is_ident("span") {
+                            quote!((start..start + len).into())
+                        } else if m.path.is_ident("start") {
+                            quote!(start)
+                        } else if m.path.is_ident("value") {
+                            let value: syn::Path = m.value()?.parse()?;
+                            quote!(#value(&remaining[..len]))
+                        } else if m.path.is_ident("intern") {
+                            match &symbol {
+                                Some(s) if s.to_token_stream().to_string()
+                                    != ty.to_token_stream().to_string() =>
+                                {
+                                    return Err(syn::Error::new_spanned(
+                                        ty,
+                                        format!(
+                                            "`#[lexer(intern)]` fields must all have the same type, `{}`",
+                                            s.to_token_stream()
+                                        ),
+                                    ));
+                                }
+                                Some(_) => {}
+                                None => symbol = Some(ty.clone()),
+                            }
+                            quote!(lexi_matic::Interner::intern(interner, &remaining[..len]))
+                        } else {
+                            return Err(m.error("unsupported attribute"));
+                        };
+                        if value.is_some() {
+                            return Err(m.error(
+                                "a field can only have one of `text`, `span`, `start`, `value` and `intern`",
+                            ));
+                        }
+                        value = Some(v);
+                        Ok(())
+                    })?;
+                }
+            }
+            let value = match value {
+                Some(value) => value,
+                None if several => {
+                    return Err(syn::Error::new_spanned(
+                        f,
+                        "fields of variants with several fields need `#[lexer(text)]`, `span`, `start`, `value = ...` or `intern`",
+                    ))
+                }
+                None => quote!((&remaining[..len]).into()),
+            };
+            values.push(value);
+        }
+        let construct = match &v.fields {
+            Fields::Unit => quote!(#name::#vn),
+            Fields::Unnamed(_) => quote!(#name::#vn(#(#values),*)),
+            Fields::Named(fields) => {
+                let names = fields.named.iter().map(|f| &f.ident);
+                quote!(#name::#vn { #(#names: #values),* })
+            }
         };
         matches.push(quote! {
             #i => {
@@ -79,13 +211,18 @@ fn derive_lexer_impl(item: DeriveInput) -> syn::Result<proc_macro2::TokenStream>
         });
 
         let mut regex = None;
+        // A `#[token]` ending with a word char.
+        let mut word = false;
         for a in &v.attrs {
             let r = if a.path().is_ident("regex") {
                 let x: LitStr = a.parse_args()?;
-                x.value()
+                parse_regex(&x, &x.value())?
             } else if a.path().is_ident("token") {
                 let x: LitStr = a.parse_args()?;
-                regex_syntax::escape(&x.value())
+                word = x
+                    .value()
+                    .ends_with(|c: char| c.is_alphanumeric() || c == '_');
+                parse_regex(&x, &regex_syntax::escape(&x.value()))?
             } else {
                 continue;
             };
@@ -105,93 +242,923 @@ fn derive_lexer_impl(item: DeriveInput) -> syn::Result<proc_macro2::TokenStream>
                     "missing a regex or token attribute",
                 ))
             }
-            Some(r) => regexes.push(r),
+            Some(r) => {
+                patterns.push((
+                    vn.to_string(),
+                    r.clone(),
+                    scope,
+                    false,
+                    !more.is_empty(),
+                    word,
+                ));
+                regexes.push(r);
+            }
         }
     }
-    regexes.extend(skip_regexes);
+    let variants = regexes.len();
+    for (k, skip) in skips.iter().enumerate() {
+        let i = (variants + k) as u32;
+        let more = &skip.more;
+        if !more.is_empty() {
+            matches.push(quote! {
+                #i => {
+                    #more
+                    // Skip.
+                    *consumed += len;
+                    if TRIVIA {
+                        return Some(Ok((start, lexi_matic::Piece::Skip(Some(#k)), start + len)));
+                    }
+                    continue;
+                }
+            });
+        }
+    }
+    let skip_labels: Vec<String> = skips.iter().map(|s| s.label.clone()).collect();
+    for s in &skips {
+        patterns.push((
+            s.label.clone(),
+            s.regex.clone(),
+            s.scope.clone(),
+            true,
+            !s.more.is_empty(),
+            false,
+        ));
+    }
+    let mut legend = Legend::default();
+    let variant_semantics = semantics
+        .iter()
+        .map(|s| legend.classify(s.as_ref()))
+        .collect::<syn::Result<Vec<_>>>()?;
+    let skip_semantics = skips
+        .iter()
+        .map(|s| legend.classify(s.semantic.as_ref()))
+        .collect::<syn::Result<Vec<_>>>()?;
+    let Legend {
+        token_types,
+        token_modifiers,
+    } = legend;
+    let mut has_more = Vec::with_capacity(regexes.len() + skips.len());
+    has_more.resize(regexes.len(), true);
+    for s in skips {
+        has_more.push(!s.more.is_empty());
+        regexes.push(s.regex);
+    }
+    let regex_strings: Vec<String> = regexes.iter().map(|r| r.to_string()).collect();
 
-    let dfa = DFA::builder()
-        .configure(
-            DFA::config()
-                // Use MatchKind::All to get longest match.
-                .match_kind(MatchKind::All)
-                .start_kind(StartKind::Anchored)
-                .accelerate(false)
-                .minimize(true),
-        )
-        .build_many(&regexes)
-        .unwrap();
-    let (little_bytes, little_p) = dfa.to_bytes_little_endian();
-    let (big_bytes, big_p) = dfa.to_bytes_big_endian();
-    let little_bytes = &little_bytes[little_p..];
-    let big_bytes = &big_bytes[big_p..];
-    let ll = little_bytes.len();
-    let bl = big_bytes.len();
-    let dfa = quote! {
-        #[repr(C, align(4))]
-        struct Align4<T>(T);
-        #[cfg(target_endian = "little")]
-        static __DFA_BYTES: &Align4<[u8; #ll]> = &Align4([ #(#little_bytes),* ]);
-        #[cfg(target_endian = "big")]
-        static __DFA_BYTES: &Align4<[u8; #bl]> = &Align4([ #(#big_bytes),* ]);
-        static DFA: std::sync::OnceLock<lexi_matic::DFA<&[u32]>> = std::sync::OnceLock::new();
-        let dfa = DFA.get_or_init(||
-            lexi_matic::DFA::from_bytes(&__DFA_BYTES.0).unwrap().0
-        );
+    let mut skip_runs = Vec::new();
+    if accelerate {
+        for (i, r) in regexes.iter().enumerate().skip(variants) {
+            if has_more[i] {
+                continue;
+            }
+            let mut others = regexes.clone();
+            others.remove(i);
+            if let Some(bytes) = accel::skip_run_bytes(r, &build_dfa(&others, false)) {
+                let k = i - variants;
+                skip_runs.push(quote! {
+                    if matches!(remaining.as_bytes()[0], #(#bytes)|*) {
+                        let len = lexi_matic::__skip_run(remaining.as_bytes(), &[#(#bytes),*]);
+                        *consumed += len;
+                        if TRIVIA {
+                            return Some(Ok((start, lexi_matic::Piece::Skip(Some(#k)), start + len)));
+                        }
+                        continue;
+                    }
+                });
+            }
+        }
+        regexes = regexes
+            .into_iter()
+            .map(accel::rewrite_negated_ascii)
+            .collect();
+    }
+    let dfa = build_dfa(&regexes, accelerate);
+    let pattern_names: Vec<String> = e
+        .variants
+        .iter()
+        .filter(|v| Some(&v.ident) != eof)
+        .map(|v| v.ident.to_string())
+        .chain(skip_labels.iter().cloned())
+        .collect();
+    let (search, dfa_bytes) = if native {
+        let search_fn = native::search_fn(&dfa);
+        let search = quote! {
+            #search_fn
+            let search = |remaining: &str| __search(remaining.as_bytes());
+        };
+        (search, None)
+    } else {
+        let search = quote! {
+            lexi_matic::__dfa_search!(search = Self::__lexi_matic_dfa_bytes());
+        };
+        (search, Some(dfa_bytes(&dfa)))
     };
 
-    let gen = if item.generics.lt_token.is_some() {
-        quote!(<'a>)
+    // The lifetime of the input is the enum's only lifetime, if any. Impls
+    // of the traits taking it and iterators get `'a` otherwise.
+    let generics = &item.generics;
+    let mut lifetimes = generics.lifetimes();
+    let borrowed = lifetimes.next().map(|l| l.lifetime.clone());
+    if let Some(l) = lifetimes.next() {
+        return Err(syn::Error::new_spanned(
+            l,
+            "a lexer can only have one lifetime, that of the input",
+        ));
+    }
+    if owned && borrowed.is_some() {
+        return Err(syn::Error::new_spanned(
+            generics,
+            "an owned lexer can't borrow from the input",
+        ));
+    }
+    let lt = borrowed
+        .clone()
+        .unwrap_or_else(|| Lifetime::new("'a", proc_macro2::Span::call_site()));
+    let input_lt = match &borrowed {
+        Some(lt) => quote!(#lt),
+        None => quote!('_),
+    };
+    // Type and const arguments of the enum, which iterators have too.
+    let type_args: Vec<_> = generics
+        .params
+        .iter()
+        .filter_map(|p| match p {
+            GenericParam::Lifetime(_) => None,
+            GenericParam::Type(t) => Some(t.ident.to_token_stream()),
+            GenericParam::Const(c) => Some(c.ident.to_token_stream()),
+        })
+        .collect();
+    let (marker_field, new_marker) = if type_args.is_empty() {
+        Default::default()
     } else {
-        quote!()
+        let (_, ty_generics, _) = generics.split_for_impl();
+        (
+            quote!(_marker: ::core::marker::PhantomData<fn() -> #name #ty_generics>,),
+            quote!(_marker: ::core::marker::PhantomData,),
+        )
+    };
+    let mut input_generics = generics.clone();
+    if borrowed.is_none() {
+        input_generics.params.insert(0, parse_quote!(#lt));
+    }
+    // Lexers with `#[lexer(intern)]` fields get an interner type parameter
+    // `I` on their iterators, defaulting to `#[lexer(interner = ...)]`. Trait
+    // impls use the default, so with a generic symbol type they only apply if
+    // it's the default interner's.
+    let interner_param = if generics.type_params().any(|t| t.ident == "I") {
+        format_ident!("__I")
+    } else {
+        format_ident!("I")
+    };
+    let mut iter_generics = input_generics.clone();
+    let mut owned_generics = generics.clone();
+    let mut trait_generics = input_generics.clone();
+    let mut owned_trait_generics = generics.clone();
+    let mut iter_args: Vec<_> = std::iter::once(quote!(#lt))
+        .chain(type_args.iter().cloned())
+        .collect();
+    let mut owned_iter_args = type_args.clone();
+    let (intern_param, intern_arg, interner_field) = match &symbol {
+        Some(symbol) => {
+            let default = interner
+                .map(|i| quote!(#i))
+                .unwrap_or_else(|| quote!(lexi_matic::SimpleInterner));
+            let param: GenericParam = parse_quote! {
+                #interner_param: lexi_matic::Interner<Symbol = #symbol> = #default
+            };
+            iter_generics.params.push(param.clone());
+            owned_generics.params.push(param);
+            if !type_args.is_empty() {
+                let bound: syn::WherePredicate =
+                    parse_quote!(#default: lexi_matic::Interner<Symbol = #symbol>);
+                trait_generics
+                    .make_where_clause()
+                    .predicates
+                    .push(bound.clone());
+                owned_trait_generics
+                    .make_where_clause()
+                    .predicates
+                    .push(bound);
+            }
+            iter_args.push(default.clone());
+            owned_iter_args.push(default);
+            (
+                quote!(, interner: &mut impl lexi_matic::Interner<Symbol = #symbol>),
+                quote!(, &mut self.interner),
+                quote!(pub interner: #interner_param,),
+            )
+        }
+        None => Default::default(),
     };
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let (trait_impl_generics, _, trait_where) = trait_generics.split_for_impl();
+    // Lexers without scopes don't implement `Grammar`, and those without
+    // semantic classes don't implement `Semantic`.
+    let grammar_impl = patterns.iter().any(|(_, _, scope, ..)| scope.is_some()).then(|| {
+        let patterns = patterns
+            .iter()
+            .map(|(name, hir, scope, skip, callback, word)| {
+                pattern(name, hir, scope.as_ref(), *skip, *callback, *word)
+            });
+        quote! {
+            impl #impl_generics lexi_matic::textmate::Grammar for #name #ty_generics #where_clause {
+                const PATTERNS: &'static [lexi_matic::textmate::Pattern] = &[#(#patterns),*];
+            }
+        }
+    });
+    let semantic_impl = (!token_types.is_empty()).then(|| {
+        quote! {
+            impl #trait_impl_generics lexi_matic::semantic::Semantic<#lt> for #name #ty_generics #trait_where {
+                const TOKEN_TYPES: &'static [&'static str] = &[#(#token_types),*];
+                const TOKEN_MODIFIERS: &'static [&'static str] = &[#(#token_modifiers),*];
+                const VARIANT_SEMANTICS: &'static [Option<(u32, u32)>] = &[#(#variant_semantics),*];
+                const SKIP_SEMANTICS: &'static [Option<(u32, u32)>] = &[#(#skip_semantics),*];
+            }
+        }
+    });
+    let (iter_impl_generics, iter_ty_generics, iter_where) = iter_generics.split_for_impl();
     let iter_name = format_ident!("{name}Iterator");
-    let lexer_impl = quote! {
-        impl <'a> lexi_matic::Lexer<'a> for #name #gen {
-            type Iterator = #iter_name<'a>;
-            fn lex(input: &'a str) -> #iter_name<'a> {
+    let error = error
+        .map(|e| quote!(#e))
+        .unwrap_or_else(|| quote!(::core::convert::Infallible));
+    // Lexers with an eof variant record that they yielded it in an `eof`
+    // field of their iterators, as `consumed` stays at the end of the input.
+    let (eof_field, new_eof, eof_param, eof_arg) = if eof.is_some() {
+        (
+            quote!(pub eof: bool,),
+            quote!(eof: false,),
+            quote!(, eof: &mut bool),
+            quote!(, &mut self.eof),
+        )
+    } else {
+        Default::default()
+    };
+    let eof = eof.map(|vn| {
+        quote! {
+            if !*eof {
+                *eof = true;
+                return Some(Ok((start, lexi_matic::Piece::Token(#name::#vn), start)));
+            }
+        }
+    });
+    let new_interner = if symbol.is_some() {
+        quote!(interner: Default::default(),)
+    } else {
+        quote!()
+    };
+    let mut lexer_impl = quote! {
+        impl #trait_impl_generics lexi_matic::Lexer<#lt> for #name #ty_generics #trait_where {
+            type Error = #error;
+            type Iterator = #iter_name<#(#iter_args),*>;
+            fn lex(input: &#lt str) -> Self::Iterator {
                 #iter_name {
                     input,
                     consumed: 0,
+                    #new_interner
+                    #new_marker
+                    #new_eof
                 }
             }
         }
 
-        #vis struct #iter_name<'a> {
-            pub input: &'a str,
-            pub consumed: usize,
-        }
-
-        impl<'a> Iterator for #iter_name<'a> {
-            type Item = Result<(usize, #name #gen, usize), lexi_matic::Error>;
-            fn next(&mut self) -> Option<Self::Item> {
-                #dfa
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// The next token, or with `TRIVIA` the next token or skipped text.
+            fn __lexi_matic_next<const TRIVIA: bool>(
+                input: &#input_lt str,
+                consumed: &mut usize
+                #intern_param
+                #eof_param
+            ) -> Option<Result<(usize, lexi_matic::Piece<Self, #error>, usize), lexi_matic::Error<#error>>> {
+                #search
 
                 loop {
-                    let start = self.consumed;
-                    let remaining = &self.input[start..];
-                    if remaining.is_empty() {
+                    let start = *consumed;
+                    if start >= input.len() {
+                        #eof
                         return None;
                     }
+                    let remaining = &input[start..];
+                    #(#skip_runs)*
 
-                    let (pat, mut len) = match lexi_matic::dfa_search_next(dfa, remaining) {
+                    let (pat, mut len) = match search(remaining) {
                         Some(t) => t,
-                        None => return Some(Err(lexi_matic::Error(start))),
+                        None => return Some(Err(lexi_matic::Error(start, lexi_matic::ErrorKind::NoMatch))),
                     };
-                    let t = match pat.as_u32() {
+                    let t = match pat {
                         #(#matches)*
                         _ => {
                             // Skip.
-                            self.consumed += len;
+                            *consumed += len;
+                            if TRIVIA {
+                                let k = pat as usize - #variants;
+                                return Some(Ok((start, lexi_matic::Piece::Skip(Some(k)), start + len)));
+                            }
                             continue;
                         }
                     };
-                    self.consumed += len;
-                    return Some(Ok((start, t, start + len)));
+                    *consumed += len;
+                    return Some(Ok((start, lexi_matic::Piece::Token(t), start + len)));
                 }
             }
         }
+
+        #vis struct #iter_name #iter_generics #iter_where {
+            pub input: &#lt str,
+            pub consumed: usize,
+            #interner_field
+            #marker_field
+            #eof_field
+        }
+
+        impl #iter_impl_generics Iterator for #iter_name #iter_ty_generics #iter_where {
+            type Item = Result<(usize, #name #ty_generics, usize), lexi_matic::Error<#error>>;
+            fn next(&mut self) -> Option<Self::Item> {
+                lexi_matic::__token(<#name #ty_generics>::__lexi_matic_next::<false>(self.input, &mut self.consumed #intern_arg #eof_arg))
+            }
+        }
     };
 
+    let variant_names: Vec<String> = e.variants.iter().map(|v| v.ident.to_string()).collect();
+    let variant_arms = e.variants.iter().enumerate().map(|(k, v)| {
+        let vn = &v.ident;
+        quote!(#name::#vn { .. } => #k,)
+    });
+    let lossless_iter_name = format_ident!("{name}LosslessIterator");
+    lexer_impl.extend(quote! {
+        impl #trait_impl_generics lexi_matic::Lossless<#lt> for #name #ty_generics #trait_where {
+            const VARIANTS: &'static [&'static str] = &[#(#variant_names),*];
+            const SKIP_LABELS: &'static [&'static str] = &[#(#skip_labels),*];
+            const REGEXES: &'static [&'static str] = &[#(#regex_strings),*];
+            type LosslessIterator = #lossless_iter_name<#(#iter_args),*>;
+            fn lex_lossless(input: &#lt str) -> Self::LosslessIterator {
+                #lossless_iter_name {
+                    input,
+                    consumed: 0,
+                    #new_interner
+                    #new_marker
+                    #new_eof
+                }
+            }
+            fn variant_index(&self) -> usize {
+                match self {
+                    #(#variant_arms)*
+                }
+            }
+        }
+
+        #semantic_impl
+
+        #grammar_impl
+
+        #vis struct #lossless_iter_name #iter_generics #iter_where {
+            pub input: &#lt str,
+            pub consumed: usize,
+            #interner_field
+            #marker_field
+            #eof_field
+        }
+
+        impl #iter_impl_generics Iterator for #lossless_iter_name #iter_ty_generics #iter_where {
+            type Item = (usize, lexi_matic::Piece<#name #ty_generics, #error>, usize);
+            fn next(&mut self) -> Option<Self::Item> {
+                match <#name #ty_generics>::__lexi_matic_next::<true>(self.input, &mut self.consumed #intern_arg #eof_arg)? {
+                    Ok(piece) => Some(piece),
+                    Err(e) => {
+                        let piece = lexi_matic::__error_piece(self.input, e);
+                        self.consumed = piece.2;
+                        Some(piece)
+                    }
+                }
+            }
+        }
+    });
+
+    if let Some(dfa_bytes) = dfa_bytes {
+        lexer_impl.extend(quote! {
+            lexi_matic::__require_dfa! {
+                impl #impl_generics #name #ty_generics #where_clause {
+                    #[inline(always)]
+                    fn __lexi_matic_dfa_bytes() -> &'static [u8] {
+                        #dfa_bytes
+                        &__DFA_BYTES.0
+                    }
+                }
+
+                impl #impl_generics lexi_matic::inspect::Inspect for #name #ty_generics #where_clause {
+                    fn dfa() -> lexi_matic::inspect::LexerDfa {
+                        lexi_matic::__load_dfa!(dfa = Self::__lexi_matic_dfa_bytes());
+                        lexi_matic::inspect::LexerDfa::__new(dfa.clone(), &[#(#pattern_names),*])
+                    }
+                }
+            }
+        });
+    }
+
+    if symbol.is_none() {
+        lexer_impl.extend(quote! {
+            impl #impl_generics lexi_matic::Stateless for #name #ty_generics #where_clause {}
+        });
+    }
+
+    if let Some(symbol) = &symbol {
+        lexer_impl.extend(quote! {
+            impl #impl_generics #name #ty_generics #where_clause {
+                /// Lex `input`, interning `#[lexer(intern)]` fields with `interner`.
+                #vis fn lex_with_interner<#interner_param: lexi_matic::Interner<Symbol = #symbol>>(
+                    input: &#input_lt str,
+                    interner: #interner_param,
+                ) -> #iter_name<#input_lt, #(#type_args,)* #interner_param> {
+                    #iter_name {
+                        input,
+                        consumed: 0,
+                        interner,
+                        #new_marker
+                        #new_eof
+                    }
+                }
+            }
+        });
+    }
+
+    if owned {
+        let owned_iter_name = format_ident!("{name}OwnedIterator");
+        let (owned_trait_impl_generics, _, owned_trait_where) =
+            owned_trait_generics.split_for_impl();
+        let (owned_impl_generics, owned_ty_generics, owned_where) = owned_generics.split_for_impl();
+        lexer_impl.extend(quote! {
+            impl #owned_trait_impl_generics lexi_matic::LexOwned for #name #ty_generics #owned_trait_where {
+                type Error = #error;
+                type Iterator = #owned_iter_name<#(#owned_iter_args),*>;
+                fn lex_owned(input: impl Into<lexi_matic::__private::Box<str>>) -> Self::Iterator {
+                    #owned_iter_name {
+                        input: input.into(),
+                        consumed: 0,
+                        #new_interner
+                        #new_marker
+                        #new_eof
+                    }
+                }
+            }
+
+            #vis struct #owned_iter_name #owned_generics #owned_where {
+                pub input: lexi_matic::__private::Box<str>,
+                pub consumed: usize,
+                #interner_field
+                #marker_field
+                #eof_field
+            }
+
+            impl #owned_impl_generics Iterator for #owned_iter_name #owned_ty_generics #owned_where {
+                type Item = Result<(usize, #name #ty_generics, usize), lexi_matic::Error<#error>>;
+                fn next(&mut self) -> Option<Self::Item> {
+                    lexi_matic::__token(<#name #ty_generics>::__lexi_matic_next::<false>(&self.input, &mut self.consumed #intern_arg #eof_arg))
+                }
+            }
+        });
+    }
+
     Ok(lexer_impl)
 }
+
+struct Skip {
+    regex: Hir,
+    label: String,
+    semantic: Option<LitStr>,
+    scope: Option<LitStr>,
+    /// Extension code, see [`extension`].
+    more: proc_macro2::TokenStream,
+}
+
+/// A `lexi_matic::textmate::Pattern` expression. Word-like `#[token]`s get a
+/// trailing `\b` so that keywords don't match prefixes of identifiers, since
+/// TextMate takes the first matching pattern rather than the longest match.
+fn pattern(
+    name: &str,
+    hir: &Hir,
+    scope: Option<&LitStr>,
+    skip: bool,
+    callback: bool,
+    word: bool,
+) -> proc_macro2::TokenStream {
+    let oniguruma = match textmate::oniguruma(hir) {
+        Ok(mut regex) => {
+            if word {
+                regex.push_str(r"\b");
+            }
+            quote!(Ok(#regex))
+        }
+        Err(e) => quote!(Err(#e)),
+    };
+    let scope = match scope {
+        Some(s) => quote!(Some(#s)),
+        None => quote!(None),
+    };
+    let spans_lines = textmate::spans_lines(hir);
+    quote! {
+        lexi_matic::textmate::Pattern {
+            name: #name,
+            skip: #skip,
+            scope: #scope,
+            callback: #callback,
+            spans_lines: #spans_lines,
+            oniguruma: #oniguruma,
+        }
+    }
+}
+
+/// The semantic token types and modifiers in order of first use.
+#[derive(Default)]
+struct Legend {
+    token_types: Vec<String>,
+    token_modifiers: Vec<String>,
+}
+
+impl Legend {
+    /// Add `semantic = "type.modifier..."` to the legend and return its token
+    /// type index and modifier bits as an `Option<(u32, u32)>` expression.
+    fn classify(&mut self, semantic: Option<&LitStr>) -> syn::Result<proc_macro2::TokenStream> {
+        let Some(semantic) = semantic else {
+            return Ok(quote!(None));
+        };
+        let value = semantic.value();
+        let mut parts = value.split('.');
+        let ty = parts.next().unwrap();
+        if ty.is_empty() || value.ends_with('.') || value.contains("..") {
+            return Err(syn::Error::new_spanned(
+                semantic,
+                "expect \"type\" or \"type.modifier...\"",
+            ));
+        }
+        let ty = index(&mut self.token_types, ty) as u32;
+        let mut modifiers = 0u32;
+        for m in parts {
+            let i = index(&mut self.token_modifiers, m);
+            if i >= 32 {
+                return Err(syn::Error::new_spanned(
+                    semantic,
+                    "at most 32 token modifiers are supported",
+                ));
+            }
+            modifiers |= 1 << i;
+        }
+        Ok(quote!(Some((#ty, #modifiers))))
+    }
+}
+
+/// The index of `s` in `v`, appending it if it isn't there.
+fn index(v: &mut Vec<String>, s: &str) -> usize {
+    v.iter().position(|x| x == s).unwrap_or_else(|| {
+        v.push(s.to_string());
+        v.len() - 1
+    })
+}
+
+/// Parse `skip = "regex"` or
+/// `skip("regex", more = f, label = "name", semantic = "comment", scope = "comment.line")`,
+/// where `more` may also be `callback`.
+fn parse_skip(m: &syn::meta::ParseNestedMeta, index: usize) -> syn::Result<Skip> {
+    if !m.input.peek(syn::token::Paren) {
+        let r: LitStr = m.value()?.parse()?;
+        return Ok(Skip {
+            regex: parse_regex(&r, &r.value())?,
+            label: r.value(),
+            semantic: None,
+            scope: None,
+            more: quote!(),
+        });
+    }
+    let content;
+    syn::parenthesized!(content in m.input);
+    let r: LitStr = content.parse()?;
+    let mut label = None;
+    let mut semantic = None;
+    let mut scope = None;
+    let mut more = None;
+    let mut callback = None;
+    while !content.is_empty() {
+        content.parse::<syn::Token![,]>()?;
+        if content.is_empty() {
+            break;
+        }
+        let key: syn::Ident = content.parse()?;
+        content.parse::<syn::Token![=]>()?;
+        if key == "label" {
+            label = Some(content.parse::<LitStr>()?.value());
+        } else if key == "semantic" {
+            semantic = Some(content.parse()?);
+        } else if key == "scope" {
+            scope = Some(content.parse()?);
+        } else if key == "more" {
+            more = Some(content.parse()?);
+        } else if key == "callback" {
+            callback = Some(content.parse()?);
+        } else {
+            return Err(syn::Error::new_spanned(key, "unsupported attribute"));
+        }
+    }
+    let label = label.unwrap_or_else(|| r.value());
+    Ok(Skip {
+        regex: parse_regex(&r, &r.value())?,
+        more: extension(more, callback, &r, &label, quote!(Some(#index)))?,
+        label,
+        semantic,
+        scope,
+    })
+}
+
+/// The code extending a match of `len` bytes with a `more` or `callback`
+/// function, shared by variants and skip patterns. `name` is the variant name
+/// or skip label for `ErrorKind::Callback`, and `skip` the index of the skip
+/// pattern for `More::Skip`, as an `Option<usize>` expression.
+fn extension(
+    more: Option<syn::Path>,
+    callback: Option<syn::Path>,
+    spanned: impl quote::ToTokens,
+    name: &str,
+    skip: proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let invalid = quote! {
+        return Some(Err(lexi_matic::Error(
+            start,
+            lexi_matic::ErrorKind::Callback { pattern: #name, len },
+        )));
+    };
+    // Lengths beyond the input aren't char boundaries either.
+    let check = quote! {
+        if !remaining.is_char_boundary(len) {
+            #invalid
+        }
+    };
+    Ok(match (more, callback) {
+        (Some(more), None) => quote! {
+            len = match #more(&remaining[..len], &remaining[len..]) {
+                Some(more) => len.saturating_add(more),
+                None => return Some(Err(lexi_matic::Error(start, lexi_matic::ErrorKind::NoMatch))),
+            };
+            #check
+        },
+        (None, Some(callback)) => quote! {
+            match #callback(&remaining[..len], &remaining[len..]) {
+                Ok(lexi_matic::More::Extend(more)) => {
+                    len = len.saturating_add(more);
+                    #check
+                }
+                Ok(lexi_matic::More::Shrink(less)) if less > 0 && less <= len => {
+                    len = less;
+                    #check
+                }
+                Ok(lexi_matic::More::Shrink(len)) => {
+                    #invalid
+                }
+                Ok(lexi_matic::More::Replace(t)) => {
+                    *consumed += len;
+                    return Some(Ok((start, lexi_matic::Piece::Token(t), start + len)));
+                }
+                Ok(lexi_matic::More::Skip) => {
+                    *consumed += len;
+                    if TRIVIA {
+                        return Some(Ok((start, lexi_matic::Piece::Skip(#skip), start + len)));
+                    }
+                    continue;
+                }
+                Err(error) => {
+                    let end = start + len;
+                    return Some(Err(lexi_matic::Error(start, lexi_matic::ErrorKind::User { end, error })));
+                }
+            }
+        },
+        (None, None) => quote!(),
+        (Some(_), Some(_)) => {
+            return Err(syn::Error::new_spanned(
+                spanned,
+                "can't have both `more` and `callback`",
+            ))
+        }
+    })
+}
+
+fn parse_regex(lit: &LitStr, regex: &str) -> syn::Result<Hir> {
+    regex_syntax::parse(regex).map_err(|e| syn::Error::new_spanned(lit, e))
+}
+
+fn build_dfa(regexes: &[Hir], accelerate: bool) -> DFA<Vec<u32>> {
+    let nfa = thompson::Compiler::new()
+        .build_many_from_hir(regexes)
+        .unwrap();
+    DFA::builder()
+        .configure(
+            DFA::config()
+                // Use MatchKind::All to get longest match.
+                .match_kind(MatchKind::All)
+                .start_kind(StartKind::Anchored)
+                .accelerate(accelerate)
+                .minimize(true),
+        )
+        .build_from_nfa(&nfa)
+        .unwrap()
+}
+
+/// Generate `static __DFA_BYTES: &Align4<[u8; N]>` holding the serialized DFA
+/// in target endianness.
+///
+/// Only the little endian serialization is emitted. The two serializations
+/// only differ in the byte order of their `u32` words, so we also emit the
+/// ranges of words that need swapping and let `lexi_matic::__dfa_bytes` swap
+/// them at compile time on big endian targets.
+fn dfa_bytes(dfa: &DFA<Vec<u32>>) -> proc_macro2::TokenStream {
+    let (little_bytes, little_p) = dfa.to_bytes_little_endian();
+    let (big_bytes, big_p) = dfa.to_bytes_big_endian();
+    let little_bytes = &little_bytes[little_p..];
+    let big_bytes = &big_bytes[big_p..];
+
+    match swapped_words(little_bytes, big_bytes) {
+        Some(swaps) => {
+            let l = little_bytes.len();
+            let swaps = swaps.iter().map(|(s, e)| quote!((#s, #e)));
+            quote! {
+                #[repr(C, align(4))]
+                struct Align4<T>(T);
+                static __DFA_BYTES: &Align4<[u8; #l]> = &Align4(lexi_matic::__dfa_bytes(
+                    [ #(#little_bytes),* ],
+                    &[ #(#swaps),* ],
+                ));
+            }
+        }
+        // Shouldn't happen, but fall back to emitting both.
+        None => {
+            let ll = little_bytes.len();
+            let bl = big_bytes.len();
+            quote! {
+                #[repr(C, align(4))]
+                struct Align4<T>(T);
+                #[cfg(target_endian = "little")]
+                static __DFA_BYTES: &Align4<[u8; #ll]> = &Align4([ #(#little_bytes),* ]);
+                #[cfg(target_endian = "big")]
+                static __DFA_BYTES: &Align4<[u8; #bl]> = &Align4([ #(#big_bytes),* ]);
+            }
+        }
+    }
+}
+
+/// Find the ranges of 4-byte words that are byte swapped between `little`
+/// and `big`. Returns `None` if they differ in some other way.
+fn swapped_words(little: &[u8], big: &[u8]) -> Option<Vec<(usize, usize)>> {
+    if little.len() != big.len() || !little.len().is_multiple_of(4) {
+        return None;
+    }
+    let mut swaps: Vec<(usize, usize)> = Vec::new();
+    for (i, (l, b)) in little.chunks(4).zip(big.chunks(4)).enumerate() {
+        let swapped = l.iter().rev().eq(b);
+        if l == b && swapped {
+            // Either way works, e.g. zeros. Extend the last range if possible
+            // to keep the number of ranges small.
+            if let Some(last) = swaps.last_mut().filter(|last| last.1 == i) {
+                last.1 = i + 1;
+            }
+        } else if l == b {
+            // Bytes, e.g. the label or the byte classes.
+        } else if swapped {
+            match swaps.last_mut().filter(|last| last.1 == i) {
+                Some(last) => last.1 = i + 1,
+                None => swaps.push((i, i + 1)),
+            }
+        } else {
+            return None;
+        }
+    }
+    Some(swaps)
+}
+
+#[cfg(test)]
+mod tests {
+    use syn::parse_quote;
+
+    use super::*;
+
+    fn grammar() -> DeriveInput {
+        parse_quote! {
+            #[lexer(skip = "//[^\n]*", skip = r"[ \t\r\n\f]+")]
+            enum Token<'a> {
+                #[token("import")]
+                Import,
+                #[token(";")]
+                Semi,
+                #[regex(r"\p{XID_Start}\p{XID_Continue}*")]
+                Ident(&'a str),
+                #[regex(r#""([^"\\]|\\.)*""#)]
+                Str(&'a str),
+            }
+        }
+    }
+
+    fn grammar_regexes() -> Vec<Hir> {
+        [
+            "import",
+            ";",
+            r"\p{XID_Start}\p{XID_Continue}*",
+            r#""([^"\\]|\\.)*""#,
+            "//[^\n]*",
+            r"[ \t\r\n\f]+",
+        ]
+        .into_iter()
+        .map(|r| regex_syntax::parse(r).unwrap())
+        .collect()
+    }
+
+    #[test]
+    fn test_swapped_words() {
+        let dfa = build_dfa(&grammar_regexes(), false);
+        let (little, lp) = dfa.to_bytes_little_endian();
+        let (big, bp) = dfa.to_bytes_big_endian();
+        let (mut little, big) = (little[lp..].to_vec(), &big[bp..]);
+        for (s, e) in swapped_words(&little, big).unwrap() {
+            for w in little[s * 4..e * 4].chunks_mut(4) {
+                w.reverse();
+            }
+        }
+        assert_eq!(little, big);
+    }
+
+    #[test]
+    fn test_oniguruma() {
+        let cases = [
+            ("a(b|cd){2,3}x?", "a(?:b|cd){2,3}x?"),
+            ("(?:ab)+|[.]", r"(?:ab)+|\."),
+            ("^a{2,}$", r"\Aa{2,}\z"),
+            ("(?m)^a$", "^a$"),
+        ];
+        for (regex, expected) in cases {
+            let hir = regex_syntax::parse(regex).unwrap();
+            assert_eq!(textmate::oniguruma(&hir).unwrap(), expected, "{regex}");
+        }
+
+        let spans_lines = |r| textmate::spans_lines(&regex_syntax::parse(r).unwrap());
+        assert!(!spans_lines("//[^\n]*\n"));
+        assert!(!spans_lines("a\n?"));
+        assert!(spans_lines("a\nb"));
+        assert!(spans_lines("[ \n]+"));
+    }
+
+    // Regression test for the size of the generated code: the DFA table
+    // should only be emitted once.
+    #[test]
+    fn test_generated_size() {
+        let dfa = build_dfa(&grammar_regexes(), false);
+        let (little, lp) = dfa.to_bytes_little_endian();
+        let table_len = little.len() - lp;
+
+        let generated = derive_lexer_impl(grammar()).unwrap().to_string();
+        let byte_literals = generated.matches("u8").count();
+        assert!(
+            byte_literals <= table_len + 10,
+            "{byte_literals} byte literals for a {table_len} bytes table"
+        );
+        // Each byte literal is at most `255u8 ,`.
+        assert!(
+            generated.len() <= table_len * 7 + 8192,
+            "{}",
+            generated.len()
+        );
+    }
+
+    #[test]
+    fn test_field_errors() {
+        let error = |item: DeriveInput| derive_lexer_impl(item).unwrap_err().to_string();
+        assert_eq!(
+            error(parse_quote! {
+                enum Token<'a> {
+                    #[regex("[a-z]+")]
+                    Ident(&'a str, Span),
+                }
+            }),
+            "fields of variants with several fields need `#[lexer(text)]`, `span`, `start`, `value = ...` or `intern`"
+        );
+        assert_eq!(
+            error(parse_quote! {
+                enum Token<'a> {
+                    #[regex("[a-z]+")]
+                    Ident(#[lexer(text, span)] &'a str),
+                }
+            }),
+            "a field can only have one of `text`, `span`, `start`, `value` and `intern`"
+        );
+        assert_eq!(
+            error(parse_quote! {
+                enum Token {
+                    #[regex("[a-z]+")]
+                    Ident { #[lexer(end)] end: usize },
+                }
+            }),
+            "unsupported attribute"
+        );
+        assert_eq!(
+            error(parse_quote! {
+                enum Token {
+                    #[regex("[a-z]+")]
+                    Ident(#[lexer(intern)] Symbol, #[lexer(intern)] u32),
+                }
+            }),
+            "`#[lexer(intern)]` fields must all have the same type, `Symbol`"
+        );
+    }
+}
@@ -0,0 +1,161 @@
+//! Native code generation: turn the minimised DFA into a `match` based state
+//! machine so the generated lexer doesn't need regex-automata at runtime.
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use proc_macro2::{Literal, TokenStream};
+use quote::quote;
+use regex_automata::{
+    dfa::{dense::DFA, Automaton},
+    util::{primitives::StateID, start::Config},
+    Anchored,
+};
+
+/// Generate `fn __search(input: &[u8]) -> Option<(u32, usize)>`, which
+/// returns the pattern and length of the longest match at the start of
+/// `input`, the same way `lexi_matic::dfa_search_next` does.
+pub fn search_fn(dfa: &DFA<Vec<u32>>) -> TokenStream {
+    let start = dfa
+        .start_state(&Config::new().anchored(Anchored::Yes))
+        .unwrap();
+    if dfa.is_dead_state(start) {
+        return quote! {
+            fn __search(_input: &[u8]) -> Option<(u32, usize)> {
+                None
+            }
+        };
+    }
+
+    // Number the live states densely in BFS order, the start state being 0.
+    let mut ids: HashMap<StateID, u32> = HashMap::new();
+    let mut states = Vec::new();
+    let mut queue = VecDeque::new();
+    ids.insert(start, 0);
+    states.push(start);
+    queue.push_back(start);
+    while let Some(s) = queue.pop_front() {
+        for b in 0..=255 {
+            let t = dfa.next_state(s, b);
+            if !dfa.is_dead_state(t) && !ids.contains_key(&t) {
+                ids.insert(t, states.len() as u32);
+                states.push(t);
+                queue.push_back(t);
+            }
+        }
+    }
+
+    let mut transition_arms = Vec::with_capacity(states.len());
+    // Pattern -> states that are match states for it.
+    let mut match_states: BTreeMap<u32, Vec<u32>> = BTreeMap::new();
+    let mut eoi_states: BTreeMap<u32, Vec<u32>> = BTreeMap::new();
+    let mut accel_arms = Vec::new();
+    for (i, &s) in states.iter().enumerate() {
+        let i = i as u32;
+        if dfa.is_accel_state(s) {
+            // Every byte but the needles loops back to this state, so jump to
+            // the next needle.
+            let si = Literal::u32_unsuffixed(i);
+            let needles = dfa.accelerator(s);
+            let set_matched = if dfa.is_match_state(s) {
+                let p = Literal::u32_unsuffixed(dfa.match_pattern(s, 0).as_u32());
+                quote! {
+                    matched = (#p, if next > i + 1 { next - 1 } else { i });
+                }
+            } else {
+                quote!()
+            };
+            accel_arms.push(quote! {
+                #si => {
+                    let next = lexi_matic::__find_needle(&[#(#needles),*], input, i + 1);
+                    #set_matched
+                    i = next;
+                    continue;
+                }
+            });
+        } else if dfa.is_match_state(s) {
+            let p = dfa.match_pattern(s, 0).as_u32();
+            match_states.entry(p).or_default().push(i);
+        }
+        let eoi = dfa.next_eoi_state(s);
+        if dfa.is_match_state(eoi) {
+            let p = dfa.match_pattern(eoi, 0).as_u32();
+            eoi_states.entry(p).or_default().push(i);
+        }
+
+        let si = Literal::u32_unsuffixed(i);
+        let byte_arms = byte_ranges(dfa, s).into_iter().map(|(lo, hi, t)| {
+            let t = Literal::u32_unsuffixed(ids[&t]);
+            let (lo, hi) = (Literal::u8_unsuffixed(lo), Literal::u8_unsuffixed(hi));
+            quote!(#lo..=#hi => #t,)
+        });
+        transition_arms.push(quote! {
+            #si => match b {
+                #(#byte_arms)*
+                _ => break,
+            },
+        });
+    }
+
+    let set_matched = |arms: &BTreeMap<u32, Vec<u32>>| -> Vec<TokenStream> {
+        arms.iter()
+            .map(|(p, ss)| {
+                let p = Literal::u32_unsuffixed(*p);
+                let ss = ss.iter().map(|s| Literal::u32_unsuffixed(*s));
+                quote!(#(#ss)|* => matched = (#p, i),)
+            })
+            .collect()
+    };
+    let match_arms = set_matched(&match_states);
+    let eoi_arms = set_matched(&eoi_states);
+
+    quote! {
+        #[allow(clippy::match_overlapping_arm, clippy::single_match)]
+        fn __search(input: &[u8]) -> Option<(u32, usize)> {
+            let mut state: u32 = 0;
+            let mut matched: (u32, usize) = (0, 0);
+            let mut i = 0;
+            loop {
+                let b = match input.get(i) {
+                    Some(b) => *b,
+                    None => {
+                        match state {
+                            #(#eoi_arms)*
+                            _ => {}
+                        }
+                        break;
+                    }
+                };
+                state = match state {
+                    #(#transition_arms)*
+                    _ => break,
+                };
+                match state {
+                    #(#accel_arms)*
+                    #(#match_arms)*
+                    _ => {}
+                }
+                i += 1;
+            }
+            if matched.1 != 0 {
+                Some(matched)
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// Group the non-dead transitions out of `s` into inclusive byte ranges.
+fn byte_ranges(dfa: &DFA<Vec<u32>>, s: StateID) -> Vec<(u8, u8, StateID)> {
+    let mut ranges: Vec<(u8, u8, StateID)> = Vec::new();
+    for b in 0..=255u8 {
+        let t = dfa.next_state(s, b);
+        if dfa.is_dead_state(t) {
+            continue;
+        }
+        match ranges.last_mut() {
+            Some((_, hi, last)) if *last == t && *hi + 1 == b => *hi = b,
+            _ => ranges.push((b, b, t)),
+        }
+    }
+    ranges
+}
@@ -0,0 +1,163 @@
+//! Translation of patterns to the Oniguruma syntax of TextMate grammars.
+use std::fmt::Write;
+
+use regex_syntax::hir::{Class, Hir, HirKind, Look};
+
+/// Translate `hir` to Oniguruma syntax, or return why it can't be.
+pub fn oniguruma(hir: &Hir) -> Result<String, &'static str> {
+    let mut out = String::new();
+    write_hir(&mut out, hir)?;
+    Ok(out)
+}
+
+fn write_hir(out: &mut String, hir: &Hir) -> Result<(), &'static str> {
+    match hir.kind() {
+        HirKind::Empty => {}
+        HirKind::Literal(l) => {
+            let s = std::str::from_utf8(&l.0).map_err(|_| "non-UTF-8 literal")?;
+            for c in s.chars() {
+                write_char(out, c, false);
+            }
+        }
+        HirKind::Class(c) => write_class(out, c)?,
+        HirKind::Look(l) => out.push_str(match l {
+            Look::Start => r"\A",
+            Look::End => r"\z",
+            Look::StartLF => "^",
+            Look::EndLF => "$",
+            Look::WordUnicode => r"\b",
+            Look::WordUnicodeNegate => r"\B",
+            _ => return Err("unsupported assertion"),
+        }),
+        HirKind::Repetition(r) => {
+            write_group(out, &r.sub, !is_atom(&r.sub))?;
+            match (r.min, r.max) {
+                (0, None) => out.push('*'),
+                (1, None) => out.push('+'),
+                (0, Some(1)) => out.push('?'),
+                (min, None) => write!(out, "{{{min},}}").unwrap(),
+                (min, Some(max)) if min == max => write!(out, "{{{min}}}").unwrap(),
+                (min, Some(max)) => write!(out, "{{{min},{max}}}").unwrap(),
+            }
+        }
+        HirKind::Capture(c) => write_group(out, &c.sub, true)?,
+        HirKind::Concat(subs) => {
+            for sub in subs {
+                let alternation = matches!(sub.kind(), HirKind::Alternation(_));
+                write_group(out, sub, alternation)?;
+            }
+        }
+        HirKind::Alternation(subs) => {
+            for (i, sub) in subs.iter().enumerate() {
+                if i > 0 {
+                    out.push('|');
+                }
+                write_hir(out, sub)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_group(out: &mut String, hir: &Hir, group: bool) -> Result<(), &'static str> {
+    if group {
+        out.push_str("(?:");
+        write_hir(out, hir)?;
+        out.push(')');
+        Ok(())
+    } else {
+        write_hir(out, hir)
+    }
+}
+
+/// Whether a repetition operator can follow `hir` without a group.
+fn is_atom(hir: &Hir) -> bool {
+    match hir.kind() {
+        HirKind::Literal(l) => std::str::from_utf8(&l.0).is_ok_and(|s| s.chars().count() == 1),
+        HirKind::Class(_) | HirKind::Capture(_) => true,
+        _ => false,
+    }
+}
+
+fn write_class(out: &mut String, class: &Class) -> Result<(), &'static str> {
+    let ranges: Vec<(char, char)> = match class {
+        Class::Unicode(c) => c.ranges().iter().map(|r| (r.start(), r.end())).collect(),
+        Class::Bytes(c) if c.is_ascii() => c
+            .ranges()
+            .iter()
+            .map(|r| (char::from(r.start()), char::from(r.end())))
+            .collect(),
+        Class::Bytes(_) => return Err("non-ASCII byte class"),
+    };
+    if ranges.is_empty() {
+        // Never matches.
+        out.push_str("(?!)");
+        return Ok(());
+    }
+    out.push('[');
+    for (start, end) in ranges {
+        write_char(out, start, true);
+        if end != start {
+            out.push('-');
+            write_char(out, end, true);
+        }
+    }
+    out.push(']');
+    Ok(())
+}
+
+fn write_char(out: &mut String, c: char, in_class: bool) {
+    let meta = if in_class {
+        r"\[]^-&"
+    } else {
+        r"\.+*?()|[]{}^$"
+    };
+    if c.is_ascii_graphic() || c == ' ' {
+        if meta.contains(c) {
+            out.push('\\');
+        }
+        out.push(c);
+    } else {
+        write!(out, r"\x{{{:X}}}", u32::from(c)).unwrap();
+    }
+}
+
+/// Whether `hir` can match text with a line break before its last char.
+/// TextMate matches line by line, so such matches can't be found.
+pub fn spans_lines(hir: &Hir) -> bool {
+    match hir.kind() {
+        HirKind::Empty | HirKind::Class(_) | HirKind::Look(_) => false,
+        HirKind::Literal(l) => l.0.iter().rev().skip(1).any(|&b| b == b'\n'),
+        HirKind::Repetition(r) => {
+            spans_lines(&r.sub) || (has_newline(&r.sub) && r.max.is_none_or(|max| max > 1))
+        }
+        HirKind::Capture(c) => spans_lines(&c.sub),
+        HirKind::Concat(subs) => subs.iter().enumerate().any(|(i, sub)| {
+            spans_lines(sub)
+                || (has_newline(sub)
+                    && subs[i + 1..]
+                        .iter()
+                        .any(|s| s.properties().maximum_len() != Some(0)))
+        }),
+        HirKind::Alternation(subs) => subs.iter().any(spans_lines),
+    }
+}
+
+/// Whether `hir` can match text containing `\n`.
+fn has_newline(hir: &Hir) -> bool {
+    match hir.kind() {
+        HirKind::Empty | HirKind::Look(_) => false,
+        HirKind::Literal(l) => l.0.contains(&b'\n'),
+        HirKind::Class(Class::Unicode(c)) => c
+            .ranges()
+            .iter()
+            .any(|r| r.start() <= '\n' && '\n' <= r.end()),
+        HirKind::Class(Class::Bytes(c)) => c
+            .ranges()
+            .iter()
+            .any(|r| r.start() <= b'\n' && b'\n' <= r.end()),
+        HirKind::Repetition(r) => r.max != Some(0) && has_newline(&r.sub),
+        HirKind::Capture(c) => has_newline(&c.sub),
+        HirKind::Concat(subs) | HirKind::Alternation(subs) => subs.iter().any(has_newline),
+    }
+}
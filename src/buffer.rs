@@ -0,0 +1,132 @@
+//! Lexed token buffers for combinator parsers.
+//!
+//! A [`TokenBuffer`] holds all tokens of an input. With the `chumsky`,
+//! `winnow` and `nom` features it can be parsed with those libraries, with
+//! spans that are offsets in the source:
+//!
+//! - `chumsky`: `&TokenBuffer<T>` is a chumsky `Input` with `SimpleSpan`s.
+//! - `winnow`: [`TokenBuffer::stream`] is a winnow `TokenSlice` and
+//!   [`Spanned`] implements `Location`.
+//! - `nom`: [`Tokens`] implements `nom::Input`.
+use alloc::vec::Vec;
+use core::ops::{Deref, Range};
+
+use crate::{Error, Lexer};
+
+#[cfg(feature = "chumsky")]
+mod chumsky_input;
+#[cfg(feature = "nom")]
+mod nom_input;
+#[cfg(feature = "winnow")]
+mod winnow_stream;
+
+/// A token and its span in the source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Spanned<T> {
+    pub start: usize,
+    pub token: T,
+    pub end: usize,
+}
+
+impl<T> Spanned<T> {
+    pub fn span(&self) -> Range<usize> {
+        self.start..self.end
+    }
+}
+
+/// Compare the token, e.g. to use tokens as literals in winnow.
+impl<T: PartialEq> PartialEq<T> for Spanned<T> {
+    fn eq(&self, other: &T) -> bool {
+        self.token == *other
+    }
+}
+
+/// All tokens of an input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenBuffer<T> {
+    tokens: Vec<Spanned<T>>,
+    input_len: usize,
+}
+
+impl<T> TokenBuffer<T> {
+    /// Lex all of `input`, stopping at the first error.
+    pub fn lex<'a>(input: &'a str) -> Result<Self, Error<T::Error>>
+    where
+        T: Lexer<'a>,
+    {
+        let tokens = T::lex(input)
+            .into_iter()
+            .map(|r| r.map(|(start, token, end)| Spanned { start, token, end }))
+            .collect::<Result<_, _>>()?;
+        Ok(TokenBuffer {
+            tokens,
+            input_len: input.len(),
+        })
+    }
+
+    /// The tokens as a slice that tracks its position in the source.
+    pub fn tokens(&self) -> Tokens<'_, T> {
+        Tokens {
+            tokens: &self.tokens,
+            end: self.input_len,
+        }
+    }
+
+    pub fn input_len(&self) -> usize {
+        self.input_len
+    }
+
+    /// The span in the source of the tokens with indices in `range`.
+    /// Empty ranges get an empty span before the token at `range.start`.
+    pub fn span(&self, range: Range<usize>) -> Range<usize> {
+        let start = self
+            .tokens
+            .get(range.start)
+            .map_or(self.input_len, |t| t.start);
+        if range.end > range.start {
+            start..self.tokens[range.end - 1].end
+        } else {
+            start..start
+        }
+    }
+}
+
+impl<T> Deref for TokenBuffer<T> {
+    type Target = [Spanned<T>];
+
+    fn deref(&self) -> &[Spanned<T>] {
+        &self.tokens
+    }
+}
+
+/// A slice of a [`TokenBuffer`].
+#[derive(Debug, PartialEq, Eq)]
+pub struct Tokens<'a, T> {
+    tokens: &'a [Spanned<T>],
+    /// The end of the input.
+    end: usize,
+}
+
+impl<T> Clone for Tokens<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Tokens<'_, T> {}
+
+impl<T> Tokens<'_, T> {
+    /// The source offset of the first token, or the end of the input if the
+    /// slice is empty, e.g. for reporting where a parser failed.
+    pub fn offset(&self) -> usize {
+        self.tokens.first().map_or(self.end, |t| t.start)
+    }
+}
+
+impl<'a, T> Deref for Tokens<'a, T> {
+    type Target = [Spanned<T>];
+
+    fn deref(&self) -> &[Spanned<T>] {
+        self.tokens
+    }
+}
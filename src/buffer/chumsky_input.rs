@@ -0,0 +1,70 @@
+use core::ops::{Range, RangeFrom};
+
+use chumsky::{
+    input::{BorrowInput, ExactSizeInput, Input, SliceInput, ValueInput},
+    span::SimpleSpan,
+};
+
+use super::{Spanned, TokenBuffer};
+
+// Cursors are token indices, spans are source offsets.
+impl<'src, T: 'src> Input<'src> for &'src TokenBuffer<T> {
+    type Span = SimpleSpan;
+    type Token = T;
+    type MaybeToken = &'src T;
+    type Cursor = usize;
+    type Cache = Self;
+
+    fn begin(self) -> (usize, Self) {
+        (0, self)
+    }
+
+    fn cursor_location(cursor: &usize) -> usize {
+        *cursor
+    }
+
+    unsafe fn next_maybe(this: &mut Self, cursor: &mut usize) -> Option<&'src T> {
+        let t = this.tokens.get(*cursor)?;
+        *cursor += 1;
+        Some(&t.token)
+    }
+
+    unsafe fn span(this: &mut Self, range: Range<&usize>) -> SimpleSpan {
+        this.span(*range.start..*range.end).into()
+    }
+}
+
+impl<'src, T: 'src> ExactSizeInput<'src> for &'src TokenBuffer<T> {
+    unsafe fn span_from(this: &mut Self, range: RangeFrom<&usize>) -> SimpleSpan {
+        let start = this.span(*range.start..*range.start).start;
+        (start..this.input_len).into()
+    }
+}
+
+impl<'src, T: Clone + 'src> ValueInput<'src> for &'src TokenBuffer<T> {
+    unsafe fn next(this: &mut Self, cursor: &mut usize) -> Option<T> {
+        Self::next_maybe(this, cursor).cloned()
+    }
+}
+
+impl<'src, T: 'src> BorrowInput<'src> for &'src TokenBuffer<T> {
+    unsafe fn next_ref(this: &mut Self, cursor: &mut usize) -> Option<&'src T> {
+        Self::next_maybe(this, cursor)
+    }
+}
+
+impl<'src, T: 'src> SliceInput<'src> for &'src TokenBuffer<T> {
+    type Slice = &'src [Spanned<T>];
+
+    fn full_slice(this: &mut Self) -> Self::Slice {
+        &this.tokens
+    }
+
+    unsafe fn slice(this: &mut Self, range: Range<&usize>) -> Self::Slice {
+        &this.tokens[*range.start..*range.end]
+    }
+
+    unsafe fn slice_from(this: &mut Self, from: RangeFrom<&usize>) -> Self::Slice {
+        &this.tokens[*from.start..]
+    }
+}
@@ -0,0 +1,63 @@
+use core::{iter::Enumerate, slice::Iter};
+
+use nom::{Input, Needed};
+
+use super::{Spanned, Tokens};
+
+impl<'a, T> Tokens<'a, T> {
+    fn with(&self, tokens: &'a [Spanned<T>], rest: &[Spanned<T>]) -> Self {
+        Tokens {
+            tokens,
+            end: rest.first().map_or(self.end, |t| t.start),
+        }
+    }
+}
+
+impl<'a, T> Input for Tokens<'a, T> {
+    type Item = &'a Spanned<T>;
+    type Iter = Iter<'a, Spanned<T>>;
+    type IterIndices = Enumerate<Self::Iter>;
+
+    fn input_len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    fn take(&self, index: usize) -> Self {
+        let (prefix, suffix) = self.tokens.split_at(index);
+        self.with(prefix, suffix)
+    }
+
+    fn take_from(&self, index: usize) -> Self {
+        Tokens {
+            tokens: &self.tokens[index..],
+            end: self.end,
+        }
+    }
+
+    fn take_split(&self, index: usize) -> (Self, Self) {
+        (self.take_from(index), self.take(index))
+    }
+
+    fn position<P>(&self, predicate: P) -> Option<usize>
+    where
+        P: Fn(Self::Item) -> bool,
+    {
+        self.tokens.iter().position(predicate)
+    }
+
+    fn iter_elements(&self) -> Self::Iter {
+        self.tokens.iter()
+    }
+
+    fn iter_indices(&self) -> Self::IterIndices {
+        self.tokens.iter().enumerate()
+    }
+
+    fn slice_index(&self, count: usize) -> Result<usize, Needed> {
+        if self.tokens.len() >= count {
+            Ok(count)
+        } else {
+            Err(Needed::new(count - self.tokens.len()))
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use core::fmt::Debug;
+
+use winnow::stream::{Location, TokenSlice};
+
+use super::{Spanned, TokenBuffer};
+
+impl<T> Location for Spanned<T> {
+    fn previous_token_end(&self) -> usize {
+        self.end
+    }
+
+    fn current_token_start(&self) -> usize {
+        self.start
+    }
+}
+
+impl<T: Debug + Clone> TokenBuffer<T> {
+    /// The tokens as a winnow stream. Its `Location` is the source offset.
+    pub fn stream(&self) -> TokenSlice<'_, Spanned<T>> {
+        TokenSlice::new(&self.tokens)
+    }
+}
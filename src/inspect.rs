@@ -0,0 +1,234 @@
+//! Inspecting the DFA of a lexer, e.g. to see why some input is lexed the
+//! way it is.
+//!
+//! Lexers with the default `dfa` code generation backend implement
+//! [`Inspect`]:
+//!
+//! ```
+//! # #[cfg(feature = "alloc")] {
+//! # use lexi_matic::{inspect::Inspect, Lexer};
+//! #[derive(Debug, Lexer)]
+//! enum Token {
+//!     #[token("if")]
+//!     If,
+//!     #[regex("[a-z]+")]
+//!     Ident,
+//! }
+//!
+//! let dfa = Token::dfa();
+//! let states = dfa.states();
+//! assert!(states[0].matches.is_empty());
+//! // After "if" both patterns match, and the first one wins.
+//! let i = states[0].next(b'i').unwrap();
+//! let f = states[i].next(b'f').unwrap();
+//! assert_eq!(states[f].matches, ["If", "Ident"]);
+//! // Render with `dot -Tsvg`.
+//! let dot = dfa.to_dot();
+//! # }
+//! ```
+#[cfg(feature = "alloc")]
+use alloc::{collections::BTreeMap, format, string::String, vec, vec::Vec};
+#[cfg(feature = "alloc")]
+use core::{fmt::Write, ops::RangeInclusive};
+
+#[cfg(feature = "alloc")]
+use regex_automata::{dfa::Automaton, util::primitives::StateID, util::start::Config, Anchored};
+
+use crate::DFA;
+
+/// Access to the DFA of a lexer, implemented by the derive for the `dfa`
+/// backend.
+pub trait Inspect {
+    /// The DFA of the lexer, for debugging.
+    fn dfa() -> LexerDfa;
+}
+
+/// The DFA of a lexer and the names of its patterns.
+#[derive(Debug, Clone)]
+pub struct LexerDfa {
+    dfa: DFA<&'static [u32]>,
+    patterns: &'static [&'static str],
+}
+
+/// A live state of a [`LexerDfa`].
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct State {
+    pub id: StateID,
+    /// The patterns matching the input that leads to this state, the one the
+    /// lexer picks first.
+    pub matches: Vec<&'static str>,
+    /// Byte ranges and the indices of the states they lead to. Bytes that
+    /// aren't in any range lead to the dead state.
+    pub transitions: Vec<(RangeInclusive<u8>, usize)>,
+    /// Whether the state is accelerated, i.e. it skips ahead with memchr.
+    pub accelerated: bool,
+}
+
+#[cfg(feature = "alloc")]
+impl State {
+    /// The index of the state after `byte`, if it isn't the dead state.
+    pub fn next(&self, byte: u8) -> Option<usize> {
+        self.transitions
+            .iter()
+            .find(|(r, _)| r.contains(&byte))
+            .map(|&(_, t)| t)
+    }
+}
+
+impl LexerDfa {
+    #[doc(hidden)]
+    pub fn __new(dfa: DFA<&'static [u32]>, patterns: &'static [&'static str]) -> Self {
+        LexerDfa { dfa, patterns }
+    }
+
+    /// The regex-automata DFA. Its start state is anchored.
+    pub fn dfa(&self) -> &DFA<&'static [u32]> {
+        &self.dfa
+    }
+
+    /// The names of the patterns by pattern ID: variants in declaration
+    /// order, except the `#[lexer(eof)]` one, then `skip` labels.
+    pub fn patterns(&self) -> &'static [&'static str] {
+        self.patterns
+    }
+
+    /// The heap memory the DFA would use if it was owned, in bytes.
+    pub fn memory_usage(&self) -> usize {
+        self.dfa.memory_usage()
+    }
+
+    /// The live states reachable from the start state, which is the first.
+    #[cfg(feature = "alloc")]
+    pub fn states(&self) -> Vec<State> {
+        let dfa = &self.dfa;
+        let start = dfa
+            .start_state(&Config::new().anchored(Anchored::Yes))
+            .unwrap();
+        if !self.is_live(start) {
+            return Vec::new();
+        }
+        let mut ids = vec![start];
+        let mut index = BTreeMap::from([(start, 0)]);
+        let mut states = Vec::new();
+        while let Some(&id) = ids.get(states.len()) {
+            let mut transitions: Vec<(RangeInclusive<u8>, usize)> = Vec::new();
+            for b in 0..=255 {
+                let t = dfa.next_state(id, b);
+                if !self.is_live(t) {
+                    continue;
+                }
+                let t = *index.entry(t).or_insert_with(|| {
+                    ids.push(t);
+                    ids.len() - 1
+                });
+                match transitions.last_mut() {
+                    Some((r, last)) if *last == t && *r.end() == b - 1 => *r = *r.start()..=b,
+                    _ => transitions.push((b..=b, t)),
+                }
+            }
+            // The patterns matching here are those of the delayed match of
+            // the end of input.
+            let eoi = dfa.next_eoi_state(id);
+            let matches = if dfa.is_match_state(eoi) {
+                (0..dfa.match_len(eoi))
+                    .map(|i| self.patterns[dfa.match_pattern(eoi, i).as_usize()])
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            states.push(State {
+                id,
+                matches,
+                transitions,
+                accelerated: dfa.is_accel_state(id),
+            });
+        }
+        states
+    }
+
+    /// Whether `id` is neither dead nor only a delayed match state: matches
+    /// are delayed by one byte in regex-automata, so after a match the next
+    /// byte leads to a match state that can only go to the dead state.
+    #[cfg(feature = "alloc")]
+    fn is_live(&self, id: StateID) -> bool {
+        let dfa = &self.dfa;
+        let delayed_match = dfa.is_match_state(id)
+            && !dfa.is_match_state(dfa.next_eoi_state(id))
+            && (0..=255).all(|b| dfa.is_dead_state(dfa.next_state(id, b)));
+        !dfa.is_dead_state(id) && !delayed_match
+    }
+
+    /// The number of live states.
+    #[cfg(feature = "alloc")]
+    pub fn state_count(&self) -> usize {
+        self.states().len()
+    }
+
+    /// Render the DFA in Graphviz dot format. States are numbered like
+    /// [`states`](Self::states), and match states are labelled with the
+    /// patterns they match.
+    #[cfg(feature = "alloc")]
+    pub fn to_dot(&self) -> String {
+        let states = self.states();
+        let mut out = String::from("digraph dfa {\n");
+        writeln!(
+            out,
+            "  // {} states, {} bytes",
+            states.len(),
+            self.memory_usage()
+        )
+        .unwrap();
+        out.push_str("  rankdir=LR;\n  node [shape=circle];\n");
+        for (i, s) in states.iter().enumerate() {
+            let mut label = format!("{i}");
+            for m in &s.matches {
+                label.push_str("\\n");
+                label.push_str(&dot_escape(m));
+            }
+            let shape = if s.matches.is_empty() {
+                ""
+            } else {
+                ", shape=doublecircle"
+            };
+            let style = if s.accelerated { ", style=bold" } else { "" };
+            writeln!(out, "  {i} [label=\"{label}\"{shape}{style}];").unwrap();
+        }
+        for (i, s) in states.iter().enumerate() {
+            // One edge per target state.
+            let mut edges: BTreeMap<usize, Vec<&RangeInclusive<u8>>> = BTreeMap::new();
+            for (r, t) in &s.transitions {
+                edges.entry(*t).or_default().push(r);
+            }
+            for (t, ranges) in edges {
+                let mut label = String::new();
+                for (k, r) in ranges.into_iter().enumerate() {
+                    if k > 0 {
+                        label.push_str(", ");
+                    }
+                    push_byte(&mut label, *r.start());
+                    if r.end() != r.start() {
+                        label.push('-');
+                        push_byte(&mut label, *r.end());
+                    }
+                }
+                writeln!(out, "  {i} -> {t} [label=\"{label}\"];").unwrap();
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+#[cfg(feature = "alloc")]
+fn push_byte(out: &mut String, b: u8) {
+    match b {
+        b'!'..=b'~' if b != b'"' && b != b'\\' => out.push(char::from(b)),
+        _ => write!(out, "\\\\x{b:02X}").unwrap(),
+    }
+}
+
+#[cfg(feature = "alloc")]
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -0,0 +1,71 @@
+//! String interning for `#[lexer(intern)]` fields.
+#[cfg(feature = "std")]
+use std::{boxed::Box, collections::HashMap, vec::Vec};
+
+/// Interns the text of `#[lexer(intern)]` fields.
+pub trait Interner {
+    type Symbol;
+    fn intern(&mut self, s: &str) -> Self::Symbol;
+}
+
+impl<I: Interner + ?Sized> Interner for &mut I {
+    type Symbol = I::Symbol;
+    fn intern(&mut self, s: &str) -> Self::Symbol {
+        (**self).intern(s)
+    }
+}
+
+/// A symbol interned by [`SimpleInterner`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(pub u32);
+
+/// An interner backed by a hash map.
+#[cfg(feature = "std")]
+#[derive(Debug, Default)]
+pub struct SimpleInterner {
+    symbols: HashMap<Box<str>, Symbol>,
+    strings: Vec<Box<str>>,
+}
+
+#[cfg(feature = "std")]
+impl SimpleInterner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The string of a symbol returned by this interner.
+    ///
+    /// # Panics
+    ///
+    /// If `symbol` isn't from this interner.
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        &self.strings[symbol.0 as usize]
+    }
+
+    /// The symbol of `s`, if it has been interned.
+    pub fn get(&self, s: &str) -> Option<Symbol> {
+        self.symbols.get(s).copied()
+    }
+
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Interner for SimpleInterner {
+    type Symbol = Symbol;
+    fn intern(&mut self, s: &str) -> Symbol {
+        if let Some(&symbol) = self.symbols.get(s) {
+            return symbol;
+        }
+        let symbol = Symbol(self.strings.len().try_into().expect("too many symbols"));
+        self.symbols.insert(s.into(), symbol);
+        self.strings.push(s.into());
+        symbol
+    }
+}
@@ -0,0 +1,55 @@
+//! Using lexers with [LALRPOP](https://github.com/lalrpop/lalrpop) parsers.
+//!
+//! Declare the token enum and `lexi_matic::Error` in the grammar's `extern`
+//! block and pass [`tokens`] to the parser:
+//!
+//! ```text
+//! extern {
+//!     type Location = usize;
+//!     type Error = lexi_matic::Error;
+//!
+//!     enum Token<'input> {
+//!         "num" => Token::Num(<&'input str>),
+//!         "+" => Token::Plus,
+//!     }
+//! }
+//! ```
+//!
+//! ```ignore
+//! let expr = calc::ExprParser::new().parse(lexi_matic::lalrpop::tokens::<Token>(input))?;
+//! ```
+use core::ops::Range;
+
+use lalrpop_util::ParseError;
+
+use crate::{Error, Lexer};
+
+/// The tokens of `input` for a LALRPOP parser.
+///
+/// The iterator ends after the first error, and `#[lexer(eof)]` tokens are
+/// dropped because LALRPOP handles the end of input itself.
+pub fn tokens<'a, T: Lexer<'a>>(
+    input: &'a str,
+) -> impl Iterator<Item = Result<(usize, T, usize), Error<T::Error>>> {
+    let mut failed = false;
+    T::lex(input)
+        .into_iter()
+        .filter(|t| !matches!(t, Ok((l, _, r)) if l == r))
+        .take_while(move |t| !core::mem::replace(&mut failed, t.is_err()))
+}
+
+/// The span of a parse error in the input. Lexical errors are reported by
+/// the parser as `ParseError::User`, and `?` converts a `lexi_matic::Error`
+/// to one too.
+pub fn error_span<T, E>(error: &ParseError<usize, T, Error<E>>) -> Range<usize> {
+    match error {
+        ParseError::InvalidToken { location } | ParseError::UnrecognizedEof { location, .. } => {
+            *location..*location
+        }
+        ParseError::UnrecognizedToken {
+            token: (l, _, r), ..
+        }
+        | ParseError::ExtraToken { token: (l, _, r) } => *l..*r,
+        ParseError::User { error } => error.span(),
+    }
+}
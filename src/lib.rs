@@ -1,42 +1,280 @@
-#![doc = include_str!("../README.md")]
-use std::fmt;
+// The examples in the README use the default `dfa` backend.
+#![cfg_attr(feature = "dfa", doc = include_str!("../README.md"))]
+#![no_std]
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
+use core::{convert::Infallible, fmt};
+
+#[cfg(feature = "std")]
+pub use intern::SimpleInterner;
+pub use intern::{Interner, Symbol};
 pub use lexi_matic_derive::Lexer;
 #[doc(hidden)]
+pub use lossless::{__error_piece, __token};
+pub use lossless::{Lossless, Piece};
+#[cfg(feature = "parallel")]
+pub use parallel::{lex_parallel, line_start};
+#[cfg(feature = "dfa")]
+#[doc(hidden)]
 pub use regex_automata::dfa::dense::DFA;
+#[cfg(feature = "dfa")]
 use regex_automata::{dfa::Automaton, util::start::Config, PatternID};
 
-#[derive(Debug)]
-pub struct Error(pub usize);
+/// A lexical error at byte offset `.0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error<E = Infallible>(pub usize, pub ErrorKind<E>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorKind<E = Infallible> {
+    /// No pattern matches, or a `more` callback returned `None`.
+    NoMatch,
+    /// A `#[lexer(callback = ...)]` function returned an error for the token
+    /// ending at `end`.
+    User { end: usize, error: E },
+    /// A `more` or `callback` function of `pattern`, a variant name or `skip`
+    /// label, made the token `len` bytes long, which is empty, beyond the end
+    /// of the input or not at a char boundary.
+    Callback { pattern: &'static str, len: usize },
+}
+
+impl<E> Error<E> {
+    /// The span of the error: the token for `User` errors, empty otherwise.
+    pub fn span(&self) -> core::ops::Range<usize> {
+        match &self.1 {
+            ErrorKind::NoMatch | ErrorKind::Callback { .. } => self.0..self.0,
+            ErrorKind::User { end, .. } => self.0..*end,
+        }
+    }
+}
 
-impl fmt::Display for Error {
+impl<E: fmt::Display> fmt::Display for Error<E> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "lexical error at {}", self.0)
+        match &self.1 {
+            ErrorKind::NoMatch => write!(f, "lexical error at {}", self.0),
+            ErrorKind::User { error, .. } => write!(f, "{error} at {}", self.0),
+            ErrorKind::Callback { pattern, len } => write!(
+                f,
+                "callback of {pattern} returned invalid token length {len} at {}",
+                self.0
+            ),
+        }
     }
 }
 
-impl std::error::Error for Error {}
+#[cfg(feature = "std")]
+impl<E: fmt::Debug + fmt::Display> std::error::Error for Error<E> {}
+
+/// What a `#[lexer(callback = ...)]` function does with a matched token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum More<T> {
+    /// Add this many bytes of the remaining input to the token.
+    Extend(usize),
+    /// Keep only this many bytes of the matched text, at least one.
+    Shrink(usize),
+    /// Emit this token instead, with the same span.
+    Replace(T),
+    /// Skip the matched text like a `skip` pattern.
+    Skip,
+}
 
 pub trait Lexer<'a>: Sized {
-    type Iterator: IntoIterator<Item = Result<(usize, Self, usize), Error>>;
+    /// The error type of callbacks, set with `#[lexer(error = ...)]`.
+    type Error;
+    type Iterator: IntoIterator<Item = Result<(usize, Self, usize), Error<Self::Error>>>;
     fn lex(input: &'a str) -> Self::Iterator;
 }
 
+/// Lexers whose tokens only depend on the input they span, implemented by
+/// the derive for lexers without `#[lexer(intern)]` fields.
+///
+/// Interned symbols depend on all earlier input, so lexing parts of the input
+/// separately, as `lex_parallel` does, would give different tokens.
+pub trait Stateless {}
+
+/// Lexers that own their input, derived with `#[lexer(owned)]`.
+///
+/// The iterator doesn't borrow anything, so it and the tokens can be stored
+/// beyond the source buffer or sent to other threads.
+#[cfg(feature = "alloc")]
+pub trait LexOwned: Sized {
+    /// The error type of callbacks, set with `#[lexer(error = ...)]`.
+    type Error;
+    type Iterator: Iterator<Item = Result<(usize, Self, usize), Error<Self::Error>>>;
+    fn lex_owned(input: impl Into<alloc::boxed::Box<str>>) -> Self::Iterator;
+}
+
+#[cfg(feature = "alloc")]
+pub mod buffer;
+#[cfg(feature = "dfa")]
+pub mod inspect;
+mod intern;
+#[cfg(feature = "lalrpop")]
+pub mod lalrpop;
+#[cfg(feature = "alloc")]
+pub mod literals;
+mod lossless;
+pub mod more;
+#[cfg(feature = "parallel")]
+mod parallel;
+#[cfg(feature = "rowan")]
+pub mod rowan;
+pub mod semantic;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod textmate;
+pub mod trace;
+
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "alloc")]
+    pub use alloc::boxed::Box;
+    #[cfg(feature = "std")]
+    pub use std::sync::OnceLock;
+}
+
+/// Bind `$name` to a `&DFA` deserialized from `$bytes`.
+///
+/// With `std` the DFA is validated once and cached in a `OnceLock`. Without
+/// `std` it is deserialized without validation on every call, which is cheap
+/// because only the headers are read.
+#[cfg(all(feature = "dfa", feature = "std"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __load_dfa {
+    ($name:ident = $bytes:expr) => {
+        static __DFA: $crate::__private::OnceLock<$crate::DFA<&'static [u32]>> =
+            $crate::__private::OnceLock::new();
+        let $name = __DFA.get_or_init(|| $crate::DFA::from_bytes($bytes).unwrap().0);
+    };
+}
+
+#[cfg(all(feature = "dfa", not(feature = "std")))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __load_dfa {
+    ($name:ident = $bytes:expr) => {
+        let $name = &$crate::__dfa_from_bytes($bytes);
+    };
+}
+
+/// Deserialize the DFA of a derived lexer without validation, so that the
+/// `unsafe` isn't in the user's crate. Only for the bytes the derive macro
+/// produces.
+#[cfg(all(feature = "dfa", not(feature = "std")))]
+#[doc(hidden)]
+pub fn __dfa_from_bytes(bytes: &'static [u8]) -> DFA<&'static [u32]> {
+    // SAFETY: the bytes are produced by `DFA::to_bytes_*` in the derive
+    // macro, so they are a valid serialized DFA.
+    unsafe { DFA::from_bytes_unchecked(bytes) }.unwrap().0
+}
+
+/// Bind `$search` to a function finding the pattern ID and length of the
+/// longest match at the start of a `&str`, with the DFA from `$bytes`.
+#[cfg(feature = "dfa")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __dfa_search {
+    ($search:ident = $bytes:expr) => {
+        $crate::__load_dfa!(dfa = $bytes);
+        let $search = |remaining: &str| {
+            $crate::dfa_search_next(dfa, remaining).map(|(p, len)| (p.as_u32(), len))
+        };
+    };
+}
+
+/// Without the `dfa` feature, a stub so that the only error is the one from
+/// [`__require_dfa`].
+#[cfg(not(feature = "dfa"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __dfa_search {
+    ($search:ident = $bytes:expr) => {
+        let $search = |_: &str| ::core::option::Option::<(u32, usize)>::None;
+    };
+}
+
+/// The items of the `dfa` backend, or an error without the `dfa` feature.
+#[cfg(feature = "dfa")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __require_dfa {
+    ($($item:tt)*) => {
+        $($item)*
+    };
+}
+
+#[cfg(not(feature = "dfa"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __require_dfa {
+    ($($item:tt)*) => {
+        ::core::compile_error!(
+            "enable the `dfa` feature of lexi-matic or use `#[lexer(codegen = \"native\")]`"
+        );
+    };
+}
+
+/// Convert a little endian serialized DFA to target endianness by swapping
+/// the bytes of the 4-byte words in `swaps` (given as word index ranges).
+#[cfg(feature = "dfa")]
+#[doc(hidden)]
+pub const fn __dfa_bytes<const N: usize>(mut bytes: [u8; N], swaps: &[(usize, usize)]) -> [u8; N] {
+    if cfg!(target_endian = "big") {
+        let mut i = 0;
+        while i < swaps.len() {
+            let (mut w, end) = swaps[i];
+            while w < end {
+                let (a, b, c, d) = (
+                    bytes[w * 4],
+                    bytes[w * 4 + 1],
+                    bytes[w * 4 + 2],
+                    bytes[w * 4 + 3],
+                );
+                bytes[w * 4] = d;
+                bytes[w * 4 + 1] = c;
+                bytes[w * 4 + 2] = b;
+                bytes[w * 4 + 3] = a;
+                w += 1;
+            }
+            i += 1;
+        }
+    }
+    bytes
+}
+
+#[cfg(feature = "dfa")]
 #[doc(hidden)]
 pub fn dfa_search_next(dfa: &DFA<&[u32]>, input: &str) -> Option<(PatternID, usize)> {
     let start = dfa
         .start_state(&Config::new().anchored(regex_automata::Anchored::Yes))
         .unwrap();
+    let input = input.as_bytes();
     let mut state = start;
     let mut matched = (start, 0);
+    let mut i = 0;
     'search: {
-        for (i, b) in input.as_bytes().iter().copied().enumerate() {
-            state = dfa.next_state(state, b);
-            if dfa.is_match_state(state) {
-                matched = (state, i);
-            } else if dfa.is_dead_state(state) {
-                break 'search;
+        while i < input.len() {
+            state = dfa.next_state(state, input[i]);
+            if dfa.is_special_state(state) {
+                if dfa.is_match_state(state) {
+                    matched = (state, i);
+                } else if dfa.is_dead_state(state) {
+                    break 'search;
+                }
+                if dfa.is_accel_state(state) {
+                    // Every byte but the needles loops back to this state.
+                    let next = __find_needle(dfa.accelerator(state), input, i + 1);
+                    if next > i + 1 && dfa.is_match_state(state) {
+                        matched = (state, next - 1);
+                    }
+                    i = next;
+                    continue;
+                }
             }
+            i += 1;
         }
         state = dfa.next_eoi_state(state);
         if dfa.is_match_state(state) {
@@ -49,3 +287,44 @@ pub fn dfa_search_next(dfa: &DFA<&[u32]>, input: &str) -> Option<(PatternID, usi
         None
     }
 }
+
+/// Find the first of the (one to three) `needles` in `haystack[from..]`.
+/// Returns `haystack.len()` if there is none.
+#[doc(hidden)]
+#[inline]
+pub fn __find_needle(needles: &[u8], haystack: &[u8], from: usize) -> usize {
+    let h = &haystack[from..];
+    let found = match *needles {
+        [a] => memchr::memchr(a, h),
+        [a, b] => memchr::memchr2(a, b, h),
+        [a, b, c] => memchr::memchr3(a, b, c, h),
+        _ => h.iter().position(|b| needles.contains(b)),
+    };
+    found.map_or(haystack.len(), |j| from + j)
+}
+
+/// Length of the run of bytes in `set` at the start of `haystack`.
+///
+/// Checks 8 bytes at a time: a byte is in `set` iff XORing it with one of the
+/// set bytes gives zero.
+#[doc(hidden)]
+#[inline]
+pub fn __skip_run(haystack: &[u8], set: &[u8]) -> usize {
+    const LO: u64 = 0x0101_0101_0101_0101;
+    const HI: u64 = 0x8080_8080_8080_8080;
+    let mut i = 0;
+    while let Some(chunk) = haystack.get(i..i + 8) {
+        let x = u64::from_ne_bytes(chunk.try_into().unwrap());
+        let mut in_set = 0;
+        for &c in set {
+            let v = x ^ (LO * c as u64);
+            // The high bit of each zero byte of `v`.
+            in_set |= !(((v & !HI) + !HI) | v) & HI;
+        }
+        if in_set != HI {
+            break;
+        }
+        i += 8;
+    }
+    i + haystack[i..].iter().take_while(|b| set.contains(b)).count()
+}
@@ -0,0 +1,423 @@
+//! Helpers for decoding literal tokens.
+//!
+//! The `*_string` functions take a whole quoted literal as matched by a
+//! `#[regex]`, so they can be used as `#[lexer(value = ...)]` callbacks:
+//!
+//! ```
+//! # use std::borrow::Cow;
+//! # use lexi_matic::{literals::{self, EscapeError}, Lexer};
+//! #[derive(Debug, Lexer)]
+//! enum Token<'a> {
+//!     #[regex(r#""([^"\\]|\\(.|\n))*""#)]
+//!     Str(#[lexer(value = literals::json_string)] Result<Cow<'a, str>, EscapeError>),
+//! }
+//! ```
+use alloc::{borrow::Cow, string::String};
+use core::fmt;
+
+pub mod number;
+
+/// Which escape sequences are recognized and how.
+///
+/// The presets [`Escapes::C`], [`Escapes::JSON`], [`Escapes::RUST`] and
+/// [`Escapes::PYTHON`] can be adjusted with struct update syntax.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Escapes {
+    /// Single character escapes, e.g. `('n', '\n')` for `\n`.
+    pub simple: &'static [(char, char)],
+    /// `\x` followed by hex digits.
+    pub hex: Hex,
+    /// `\u` followed by exactly four hex digits. UTF-16 surrogate pairs
+    /// written as two escapes, like in JSON, are combined.
+    pub unicode4: bool,
+    /// `\U` followed by exactly eight hex digits.
+    pub unicode8: bool,
+    /// `\u{...}` with one to six hex digits.
+    pub unicode_braced: bool,
+    /// `\` followed by one to three octal digits, up to `\377`.
+    pub octal: bool,
+    /// What a backslash before a line break does.
+    pub line_continuation: LineContinuation,
+    /// Keep unknown escapes as they are, e.g. `\q` is `\q`, instead of
+    /// reporting an error.
+    pub keep_unknown: bool,
+}
+
+/// How `\x` escapes are decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hex {
+    /// `\x` is not an escape.
+    None,
+    /// Exactly two hex digits, at most `max`.
+    Two { max: u8 },
+    /// One or more hex digits, like in C.
+    Any,
+}
+
+/// What a backslash before a line break does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineContinuation {
+    /// It's an error (or kept, with `keep_unknown`).
+    None,
+    /// Both the backslash and the line break are removed.
+    Join,
+    /// The backslash, the line break and the whitespace at the start of the
+    /// next line are removed, like in Rust.
+    SkipWhitespace,
+}
+
+const C_SIMPLE: &[(char, char)] = &[
+    ('a', '\x07'),
+    ('b', '\x08'),
+    ('f', '\x0c'),
+    ('n', '\n'),
+    ('r', '\r'),
+    ('t', '\t'),
+    ('v', '\x0b'),
+    ('\\', '\\'),
+    ('\'', '\''),
+    ('"', '"'),
+    ('?', '?'),
+];
+
+const JSON_SIMPLE: &[(char, char)] = &[
+    ('"', '"'),
+    ('\\', '\\'),
+    ('/', '/'),
+    ('b', '\x08'),
+    ('f', '\x0c'),
+    ('n', '\n'),
+    ('r', '\r'),
+    ('t', '\t'),
+];
+
+const RUST_SIMPLE: &[(char, char)] = &[
+    ('n', '\n'),
+    ('r', '\r'),
+    ('t', '\t'),
+    ('\\', '\\'),
+    ('0', '\0'),
+    ('\'', '\''),
+    ('"', '"'),
+];
+
+const PYTHON_SIMPLE: &[(char, char)] = &[
+    ('\\', '\\'),
+    ('\'', '\''),
+    ('"', '"'),
+    ('a', '\x07'),
+    ('b', '\x08'),
+    ('f', '\x0c'),
+    ('n', '\n'),
+    ('r', '\r'),
+    ('t', '\t'),
+    ('v', '\x0b'),
+];
+
+impl Escapes {
+    /// C string and character literal escapes, including `\u` and `\U`.
+    pub const C: Escapes = Escapes {
+        simple: C_SIMPLE,
+        hex: Hex::Any,
+        unicode4: true,
+        unicode8: true,
+        unicode_braced: false,
+        octal: true,
+        line_continuation: LineContinuation::Join,
+        keep_unknown: false,
+    };
+
+    /// JSON string escapes.
+    pub const JSON: Escapes = Escapes {
+        simple: JSON_SIMPLE,
+        hex: Hex::None,
+        unicode4: true,
+        unicode8: false,
+        unicode_braced: false,
+        octal: false,
+        line_continuation: LineContinuation::None,
+        keep_unknown: false,
+    };
+
+    /// Rust string and char literal escapes.
+    pub const RUST: Escapes = Escapes {
+        simple: RUST_SIMPLE,
+        hex: Hex::Two { max: 0x7f },
+        unicode4: false,
+        unicode8: false,
+        unicode_braced: true,
+        octal: false,
+        line_continuation: LineContinuation::SkipWhitespace,
+        keep_unknown: false,
+    };
+
+    /// Python `str` literal escapes, except `\N{...}`.
+    pub const PYTHON: Escapes = Escapes {
+        simple: PYTHON_SIMPLE,
+        hex: Hex::Two { max: 0xff },
+        unicode4: true,
+        unicode8: true,
+        unicode_braced: false,
+        octal: true,
+        line_continuation: LineContinuation::Join,
+        keep_unknown: true,
+    };
+
+    /// Decode the escape sequences in `s`.
+    ///
+    /// Returns `s` itself if it doesn't contain any backslash. Error offsets
+    /// are relative to `s`.
+    pub fn unescape<'a>(&self, s: &'a str) -> Result<Cow<'a, str>, EscapeError> {
+        let Some(first) = s.find('\\') else {
+            return Ok(Cow::Borrowed(s));
+        };
+        let mut out = String::with_capacity(s.len());
+        out.push_str(&s[..first]);
+        let mut i = first;
+        while i < s.len() {
+            let Some(next) = s[i..].find('\\') else {
+                out.push_str(&s[i..]);
+                break;
+            };
+            out.push_str(&s[i..i + next]);
+            i += next;
+            i = self.unescape_one(s, i, &mut out)?;
+        }
+        Ok(Cow::Owned(out))
+    }
+
+    /// Strip the first and last character (the quotes) of `s` and decode the
+    /// escape sequences in between.
+    ///
+    /// Error offsets are relative to `s`, including the opening quote.
+    pub fn unquote<'a>(&self, s: &'a str) -> Result<Cow<'a, str>, EscapeError> {
+        let mut chars = s.chars();
+        let (Some(open), Some(_)) = (chars.next(), chars.next_back()) else {
+            return Err(EscapeError {
+                offset: 0,
+                len: s.len(),
+                kind: EscapeErrorKind::Unquoted,
+            });
+        };
+        let open = open.len_utf8();
+        self.unescape(chars.as_str()).map_err(|e| EscapeError {
+            offset: e.offset + open,
+            ..e
+        })
+    }
+
+    /// Decode the escape sequence starting with the backslash at `s[i]`.
+    /// Returns the offset after it.
+    fn unescape_one(&self, s: &str, i: usize, out: &mut String) -> Result<usize, EscapeError> {
+        let error = |len: usize, kind| EscapeError {
+            offset: i,
+            len,
+            kind,
+        };
+        let Some(c) = s[i + 1..].chars().next() else {
+            return Err(error(1, EscapeErrorKind::TrailingBackslash));
+        };
+        let after = i + 1 + c.len_utf8();
+
+        if let Some(&(_, decoded)) = self.simple.iter().find(|(e, _)| *e == c) {
+            out.push(decoded);
+            return Ok(after);
+        }
+        match c {
+            '\n' | '\r' if self.line_continuation != LineContinuation::None => {
+                let mut end = after;
+                if c == '\r' && s[end..].starts_with('\n') {
+                    end += 1;
+                }
+                if self.line_continuation == LineContinuation::SkipWhitespace {
+                    let rest = &s[end..];
+                    end += rest.len() - rest.trim_start_matches([' ', '\t', '\n', '\r']).len();
+                }
+                return Ok(end);
+            }
+            'x' if self.hex != Hex::None => {
+                let digits = hex_digits(
+                    &s[after..],
+                    if self.hex == Hex::Any { usize::MAX } else { 2 },
+                );
+                if digits == 0 || (self.hex != Hex::Any && digits != 2) {
+                    return Err(error(2 + digits, EscapeErrorKind::InvalidHex));
+                }
+                let end = after + digits;
+                let value = u32::from_str_radix(&s[after..end], 16).ok();
+                let value = match (self.hex, value) {
+                    (Hex::Two { max }, Some(v)) if v <= max.into() => Some(v),
+                    (Hex::Any, v) => v,
+                    _ => None,
+                };
+                return push_code_point(out, value, error(end - i, EscapeErrorKind::OutOfRange))
+                    .map(|()| end);
+            }
+            'u' if self.unicode_braced && s[after..].starts_with('{') => {
+                let open = after + 1;
+                let digits = hex_digits(&s[open..], 6);
+                let close = open + digits;
+                if digits == 0 || !s[close..].starts_with('}') {
+                    return Err(error(close - i, EscapeErrorKind::InvalidUnicode));
+                }
+                let value = u32::from_str_radix(&s[open..close], 16).ok();
+                return push_code_point(
+                    out,
+                    value,
+                    error(close + 1 - i, EscapeErrorKind::OutOfRange),
+                )
+                .map(|()| close + 1);
+            }
+            'u' if self.unicode4 => {
+                let end = after + 4;
+                if hex_digits(&s[after..], 4) != 4 {
+                    return Err(error(
+                        2 + hex_digits(&s[after..], 4),
+                        EscapeErrorKind::InvalidUnicode,
+                    ));
+                }
+                let high = u32::from_str_radix(&s[after..end], 16).unwrap();
+                if (0xd800..0xdc00).contains(&high)
+                    && s[end..].starts_with("\\u")
+                    && hex_digits(&s[end + 2..], 4) == 4
+                {
+                    let low = u32::from_str_radix(&s[end + 2..end + 6], 16).unwrap();
+                    if (0xdc00..0xe000).contains(&low) {
+                        let value = 0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00);
+                        return push_code_point(
+                            out,
+                            Some(value),
+                            error(end + 6 - i, EscapeErrorKind::OutOfRange),
+                        )
+                        .map(|()| end + 6);
+                    }
+                }
+                return push_code_point(
+                    out,
+                    Some(high),
+                    error(end - i, EscapeErrorKind::OutOfRange),
+                )
+                .map(|()| end);
+            }
+            'U' if self.unicode8 => {
+                let end = after + 8;
+                if hex_digits(&s[after..], 8) != 8 {
+                    return Err(error(
+                        2 + hex_digits(&s[after..], 8),
+                        EscapeErrorKind::InvalidUnicode,
+                    ));
+                }
+                let value = u32::from_str_radix(&s[after..end], 16).ok();
+                return push_code_point(out, value, error(end - i, EscapeErrorKind::OutOfRange))
+                    .map(|()| end);
+            }
+            '0'..='7' if self.octal => {
+                let digits = s[i + 1..]
+                    .bytes()
+                    .take(3)
+                    .take_while(|b| (b'0'..=b'7').contains(b))
+                    .count();
+                let end = i + 1 + digits;
+                let value = u32::from_str_radix(&s[i + 1..end], 8)
+                    .ok()
+                    .filter(|v| *v <= 0o377);
+                return push_code_point(out, value, error(end - i, EscapeErrorKind::OutOfRange))
+                    .map(|()| end);
+            }
+            _ => {}
+        }
+        if self.keep_unknown {
+            out.push('\\');
+            out.push(c);
+            Ok(after)
+        } else {
+            Err(error(after - i, EscapeErrorKind::UnknownEscape))
+        }
+    }
+}
+
+/// Number of leading hex digits in `s`, at most `max`.
+fn hex_digits(s: &str, max: usize) -> usize {
+    s.bytes()
+        .take(max)
+        .take_while(u8::is_ascii_hexdigit)
+        .count()
+}
+
+fn push_code_point(
+    out: &mut String,
+    value: Option<u32>,
+    error: EscapeError,
+) -> Result<(), EscapeError> {
+    match value.and_then(char::from_u32) {
+        Some(c) => {
+            out.push(c);
+            Ok(())
+        }
+        None => Err(error),
+    }
+}
+
+/// An invalid escape sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EscapeError {
+    /// Offset of the escape sequence.
+    pub offset: usize,
+    /// Length of the (invalid part of the) escape sequence.
+    pub len: usize,
+    pub kind: EscapeErrorKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EscapeErrorKind {
+    /// A backslash followed by a character that doesn't start an escape.
+    UnknownEscape,
+    /// A backslash at the end of the input.
+    TrailingBackslash,
+    /// `\x` without the right number of hex digits.
+    InvalidHex,
+    /// Malformed `\u`, `\U` or `\u{...}`.
+    InvalidUnicode,
+    /// An escape for something that isn't a valid char, e.g. a surrogate or
+    /// a too large value.
+    OutOfRange,
+    /// Input to `unquote` that's too short to have quotes.
+    Unquoted,
+}
+
+impl fmt::Display for EscapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let what = match self.kind {
+            EscapeErrorKind::UnknownEscape => "unknown escape sequence",
+            EscapeErrorKind::TrailingBackslash => "trailing backslash",
+            EscapeErrorKind::InvalidHex => "invalid hex escape",
+            EscapeErrorKind::InvalidUnicode => "invalid unicode escape",
+            EscapeErrorKind::OutOfRange => "escape out of range",
+            EscapeErrorKind::Unquoted => "missing quotes",
+        };
+        write!(f, "{what} at {}", self.offset)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for EscapeError {}
+
+/// Decode a quoted C string or character literal.
+pub fn c_string(s: &str) -> Result<Cow<'_, str>, EscapeError> {
+    Escapes::C.unquote(s)
+}
+
+/// Decode a quoted JSON string.
+pub fn json_string(s: &str) -> Result<Cow<'_, str>, EscapeError> {
+    Escapes::JSON.unquote(s)
+}
+
+/// Decode a quoted Rust string or char literal.
+pub fn rust_string(s: &str) -> Result<Cow<'_, str>, EscapeError> {
+    Escapes::RUST.unquote(s)
+}
+
+/// Decode a quoted Python `str` literal (without prefix).
+pub fn python_string(s: &str) -> Result<Cow<'_, str>, EscapeError> {
+    Escapes::PYTHON.unquote(s)
+}
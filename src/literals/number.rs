@@ -0,0 +1,243 @@
+//! Parsing integer and float literals.
+//!
+//! ```
+//! # use lexi_matic::{literals::number::{self, Number, NumberError}, Lexer};
+//! #[derive(Debug, Lexer)]
+//! enum Token<'a> {
+//!     #[regex(r"[0-9][0-9a-zA-Z_]*(\.[0-9][0-9_]*)?([eE][+-]?[0-9_]+)?[a-z0-9]*")]
+//!     Number(#[lexer(value = number::rust_number)] Result<Number<'a>, NumberError>),
+//! }
+//! ```
+use core::fmt;
+
+/// What number literals look like.
+///
+/// The presets [`NumberFormat::RUST`], [`NumberFormat::PYTHON`] and
+/// [`NumberFormat::GO`] can be adjusted with struct update syntax.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NumberFormat {
+    /// `0x`, `0o` and `0b` prefixes for integers.
+    pub prefixes: bool,
+    /// `_` separators between digits.
+    pub separators: bool,
+    /// Allowed integer suffixes and the largest value for each.
+    pub int_suffixes: &'static [(&'static str, u128)],
+    /// Allowed float suffixes and the largest value for each. Decimal
+    /// integers with these suffixes are floats.
+    pub float_suffixes: &'static [(&'static str, f64)],
+}
+
+const RUST_INT_SUFFIXES: &[(&str, u128)] = &[
+    ("u8", u8::MAX as u128),
+    ("u16", u16::MAX as u128),
+    ("u32", u32::MAX as u128),
+    ("u64", u64::MAX as u128),
+    ("u128", u128::MAX),
+    ("usize", u64::MAX as u128),
+    ("i8", i8::MAX as u128),
+    ("i16", i16::MAX as u128),
+    ("i32", i32::MAX as u128),
+    ("i64", i64::MAX as u128),
+    ("i128", i128::MAX as u128),
+    ("isize", i64::MAX as u128),
+];
+
+const RUST_FLOAT_SUFFIXES: &[(&str, f64)] = &[("f32", f32::MAX as f64), ("f64", f64::MAX)];
+
+impl NumberFormat {
+    /// Rust integer and float literals. `usize` and `isize` are assumed to be
+    /// 64 bits. Signed integers are limited to their positive maximum.
+    pub const RUST: NumberFormat = NumberFormat {
+        prefixes: true,
+        separators: true,
+        int_suffixes: RUST_INT_SUFFIXES,
+        float_suffixes: RUST_FLOAT_SUFFIXES,
+    };
+
+    /// Python `int`, `float` and imaginary (`j`) literals.
+    pub const PYTHON: NumberFormat = NumberFormat {
+        prefixes: true,
+        separators: true,
+        int_suffixes: &[],
+        float_suffixes: &[("j", f64::MAX), ("J", f64::MAX)],
+    };
+
+    /// Go integer, float and imaginary (`i`) literals.
+    pub const GO: NumberFormat = NumberFormat {
+        prefixes: true,
+        separators: true,
+        int_suffixes: &[("i", u128::MAX)],
+        float_suffixes: &[("i", f64::MAX)],
+    };
+
+    /// Parse a number literal. Error offsets are relative to `s`.
+    pub fn parse<'a>(&self, s: &'a str) -> Result<Number<'a>, NumberError> {
+        let bytes = s.as_bytes();
+        let error = |offset: usize, len: usize, kind| Err(NumberError { offset, len, kind });
+
+        let (radix, digits_start) = match bytes {
+            [b'0', b'x' | b'X', ..] if self.prefixes => (16, 2),
+            [b'0', b'o' | b'O', ..] if self.prefixes => (8, 2),
+            [b'0', b'b' | b'B', ..] if self.prefixes => (2, 2),
+            _ => (10, 0),
+        };
+        // Scan the integer part. Decimal digits that are too large for the
+        // radix are errors rather than the start of the suffix.
+        let mut i = digits_start;
+        let mut digits = 0;
+        while let Some(&b) = bytes.get(i) {
+            if b == b'_' && self.separators {
+                i += 1;
+                continue;
+            }
+            match (b as char).to_digit(radix.max(10)) {
+                Some(d) if d < radix => digits += 1,
+                Some(_) => return error(i, 1, NumberErrorKind::InvalidDigit),
+                None => break,
+            }
+            i += 1;
+        }
+        if digits == 0 {
+            return error(0, i.max(1).min(s.len()), NumberErrorKind::NoDigits);
+        }
+        let int_end = i;
+
+        // Fraction and exponent.
+        let mut is_float = false;
+        if radix == 10 {
+            if bytes.get(i) == Some(&b'.') && bytes.get(i + 1).is_some_and(u8::is_ascii_digit) {
+                is_float = true;
+                i += 1;
+                i += self.digits_len(&bytes[i..]);
+            }
+            if let Some(b'e' | b'E') = bytes.get(i) {
+                let mut j = i + 1;
+                if let Some(b'+' | b'-') = bytes.get(j) {
+                    j += 1;
+                }
+                let exp_digits = self.digits_len(&bytes[j..]);
+                if bytes[j..j + exp_digits].iter().any(u8::is_ascii_digit) {
+                    is_float = true;
+                    i = j + exp_digits;
+                } else {
+                    return error(i, j + exp_digits - i, NumberErrorKind::NoDigits);
+                }
+            }
+        }
+        let (number, suffix) = s.split_at(i);
+
+        let float_suffix = self.float_suffixes.iter().find(|(sfx, _)| *sfx == suffix);
+        let int_suffix = self.int_suffixes.iter().find(|(sfx, _)| *sfx == suffix);
+        let is_float = is_float || (radix == 10 && float_suffix.is_some() && int_suffix.is_none());
+        if is_float {
+            let Some(&(_, max)) = float_suffix.or(suffix.is_empty().then_some(&("", f64::MAX)))
+            else {
+                return error(i, suffix.len(), NumberErrorKind::InvalidSuffix);
+            };
+            let value: f64 = number.replace('_', "").parse().unwrap();
+            if value > max {
+                return error(0, i, NumberErrorKind::Overflow);
+            }
+            return Ok(Number::Float { value, suffix });
+        }
+
+        let Some(&(_, max)) = int_suffix.or(suffix.is_empty().then_some(&("", u128::MAX))) else {
+            return error(i, suffix.len(), NumberErrorKind::InvalidSuffix);
+        };
+        let mut value: u128 = 0;
+        for b in s[digits_start..int_end].bytes().filter(|b| *b != b'_') {
+            let d = (b as char).to_digit(radix).unwrap();
+            value = match value
+                .checked_mul(radix.into())
+                .and_then(|v| v.checked_add(d.into()))
+            {
+                Some(v) => v,
+                None => return error(0, int_end, NumberErrorKind::Overflow),
+            };
+        }
+        if value > max {
+            return error(0, int_end, NumberErrorKind::Overflow);
+        }
+        Ok(Number::Int {
+            value,
+            radix,
+            suffix,
+        })
+    }
+
+    /// Length of the leading decimal digits and separators of `bytes`.
+    fn digits_len(&self, bytes: &[u8]) -> usize {
+        bytes
+            .iter()
+            .take_while(|b| b.is_ascii_digit() || (**b == b'_' && self.separators))
+            .count()
+    }
+}
+
+/// A parsed number literal.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Number<'a> {
+    Int {
+        value: u128,
+        radix: u32,
+        /// The suffix, or `""`.
+        suffix: &'a str,
+    },
+    Float {
+        value: f64,
+        /// The suffix, or `""`.
+        suffix: &'a str,
+    },
+}
+
+/// An invalid number literal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberError {
+    /// Offset of the invalid part of the literal.
+    pub offset: usize,
+    /// Length of the invalid part of the literal.
+    pub len: usize,
+    pub kind: NumberErrorKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberErrorKind {
+    /// No digits after a prefix or in an exponent.
+    NoDigits,
+    /// A digit that is too large for the radix, e.g. `2` in `0b102`.
+    InvalidDigit,
+    /// A suffix that isn't allowed.
+    InvalidSuffix,
+    /// The value is too large for the suffix (or `u128`).
+    Overflow,
+}
+
+impl fmt::Display for NumberError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let what = match self.kind {
+            NumberErrorKind::NoDigits => "missing digits",
+            NumberErrorKind::InvalidDigit => "invalid digit",
+            NumberErrorKind::InvalidSuffix => "invalid suffix",
+            NumberErrorKind::Overflow => "number too large",
+        };
+        write!(f, "{what} at {}", self.offset)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NumberError {}
+
+/// Parse a Rust integer or float literal.
+pub fn rust_number(s: &str) -> Result<Number<'_>, NumberError> {
+    NumberFormat::RUST.parse(s)
+}
+
+/// Parse a Python number literal.
+pub fn python_number(s: &str) -> Result<Number<'_>, NumberError> {
+    NumberFormat::PYTHON.parse(s)
+}
+
+/// Parse a Go number literal.
+pub fn go_number(s: &str) -> Result<Number<'_>, NumberError> {
+    NumberFormat::GO.parse(s)
+}
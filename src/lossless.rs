@@ -0,0 +1,71 @@
+//! Lexing that keeps skipped text and errors.
+use core::convert::Infallible;
+
+use crate::{trace::Trace, Error, ErrorKind, Lexer};
+
+/// What a piece of the input yielded by [`Lossless::lex_lossless`] is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Piece<T, E = Infallible> {
+    Token(T),
+    /// Text skipped by the `skip` pattern with this index in `SKIP_LABELS`,
+    /// also if its own callback returned `More::Skip`, or `None` if the
+    /// callback of a variant returned `More::Skip`.
+    Skip(Option<usize>),
+    /// Text that couldn't be lexed: the token for `User` errors, one char
+    /// otherwise.
+    Error(ErrorKind<E>),
+}
+
+/// Lexers that can yield every piece of the input, implemented by the derive.
+///
+/// The pieces yielded by `lex_lossless` are in order and cover the whole
+/// input without gaps or overlap. Lexing continues after errors.
+pub trait Lossless<'a>: Lexer<'a> {
+    /// Names of the variants in declaration order.
+    const VARIANTS: &'static [&'static str];
+    /// Labels of the `skip` patterns in order.
+    const SKIP_LABELS: &'static [&'static str];
+    /// The regexes of the variants in declaration order, except the
+    /// `#[lexer(eof)]` one, then of the `skip` patterns, in the syntax of the
+    /// regex crate.
+    const REGEXES: &'static [&'static str];
+    type LosslessIterator: Iterator<Item = (usize, Piece<Self, Self::Error>, usize)>;
+
+    fn lex_lossless(input: &'a str) -> Self::LosslessIterator;
+
+    /// Index of the token's variant in `VARIANTS`.
+    fn variant_index(&self) -> usize;
+
+    /// Lex `input` like `lex`, writing a line for every token, skipped text
+    /// and error to `out`. See [`trace`](crate::trace). Unlike `lex`, lexing
+    /// continues after errors.
+    fn trace<W>(input: &'a str, out: W) -> Trace<'a, Self, W> {
+        Trace::new(input, out)
+    }
+}
+
+/// Turn an error into an error piece, and where to continue lexing.
+#[doc(hidden)]
+pub fn __error_piece<T, E>(
+    input: &str,
+    Error(start, kind): Error<E>,
+) -> (usize, Piece<T, E>, usize) {
+    let end = match kind {
+        ErrorKind::User { end, .. } if end > start => end,
+        _ => start + input[start..].chars().next().map_or(0, char::len_utf8),
+    };
+    (start, Piece::Error(kind), end)
+}
+
+type Next<T, E> = Option<Result<(usize, Piece<T, E>, usize), Error<E>>>;
+
+/// The token of a non-lossless `__lexi_matic_next` result.
+#[doc(hidden)]
+#[inline(always)]
+pub fn __token<T, E>(r: Next<T, E>) -> Option<Result<(usize, T, usize), Error<E>>> {
+    match r? {
+        Ok((start, Piece::Token(t), end)) => Some(Ok((start, t, end))),
+        Ok(_) => unreachable!(),
+        Err(e) => Some(Err(e)),
+    }
+}
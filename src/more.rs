@@ -0,0 +1,123 @@
+//! Ready-made `more` callbacks for literals whose closing delimiter depends
+//! on the opening one.
+//!
+//! Each callback takes the opener as matched by the variant's `#[regex]` and
+//! returns the length of the rest of the literal, including the closing
+//! delimiter, or `None` if it is unterminated.
+//!
+//! ```
+//! # #[cfg(feature = "dfa")] {
+//! # use lexi_matic::{more, Lexer};
+//! #[derive(Debug, Lexer)]
+//! enum Token<'a> {
+//!     #[regex(r##"r#*""##)]
+//!     #[lexer(more = more::rust_raw_string)]
+//!     RawStr(&'a str),
+//!     #[regex(r"\[=*\[")]
+//!     #[lexer(more = more::lua_long_bracket)]
+//!     LongStr(&'a str),
+//! }
+//! # }
+//! ```
+
+/// Rust raw strings. The opener is `r"`, `r#"`, `r##"` etc., optionally with
+/// a `b` or `c` prefix.
+///
+/// ```
+/// # use lexi_matic::more::rust_raw_string;
+/// assert_eq!(rust_raw_string(r##"r#""##, r##"a"b"# + 1"##), Some(5));
+/// ```
+pub fn rust_raw_string(matched: &str, remaining: &str) -> Option<usize> {
+    let hashes = matched.bytes().filter(|&b| b == b'#').count();
+    find_closing(remaining, |rest| {
+        let rest = rest.strip_prefix('"')?;
+        rest.get(..hashes)?
+            .bytes()
+            .all(|b| b == b'#')
+            .then_some(1 + hashes)
+    })
+}
+
+/// Lua long strings and comments. The opener is `[[`, `[=[`, `[==[` etc.,
+/// optionally preceded by `--`.
+///
+/// ```
+/// # use lexi_matic::more::lua_long_bracket;
+/// assert_eq!(lua_long_bracket("[=[", "a]]b]=] c"), Some(7));
+/// ```
+pub fn lua_long_bracket(matched: &str, remaining: &str) -> Option<usize> {
+    let level = matched.bytes().filter(|&b| b == b'=').count();
+    find_closing(remaining, |rest| {
+        let rest = rest.strip_prefix(']')?;
+        (rest.get(..level)?.bytes().all(|b| b == b'=') && rest[level..].starts_with(']'))
+            .then_some(level + 2)
+    })
+}
+
+/// C++ raw strings. The opener is `R"delim(`, optionally with an encoding
+/// prefix such as `u8R"delim(`.
+///
+/// ```
+/// # use lexi_matic::more::cpp_raw_string;
+/// assert_eq!(cpp_raw_string(r#"R"x("#, r#"a)"b)x" c"#), Some(7));
+/// ```
+pub fn cpp_raw_string(matched: &str, remaining: &str) -> Option<usize> {
+    let open = matched.find('"')?;
+    let delim = matched[open + 1..].strip_suffix('(')?;
+    find_closing(remaining, |rest| {
+        let rest = rest.strip_prefix(')')?.strip_prefix(delim)?;
+        rest.starts_with('"').then_some(delim.len() + 2)
+    })
+}
+
+/// Here documents. The opener is `<<DELIM`, where the delimiter may be
+/// quoted with `'` or `"`. With `<<-DELIM` or `<<~DELIM` the closing line may
+/// be indented.
+///
+/// The body starts on the line after the opener and the token ends at the
+/// end of the line consisting of just the delimiter, not including its line
+/// break. Anything else on the opener's line is part of the token.
+///
+/// ```
+/// # use lexi_matic::more::heredoc;
+/// assert_eq!(heredoc("<<EOF", "\nEOFX\nEOF\nrest"), Some(9));
+/// ```
+pub fn heredoc(matched: &str, remaining: &str) -> Option<usize> {
+    let opener = matched.strip_prefix("<<")?;
+    let (indented, opener) = match opener.strip_prefix(['-', '~']) {
+        Some(opener) => (true, opener),
+        None => (false, opener),
+    };
+    let delim = opener.trim_matches(['\'', '"']);
+    if delim.is_empty() {
+        return None;
+    }
+    // Skip the rest of the opener's line.
+    let mut line_start = remaining.find('\n')? + 1;
+    loop {
+        let line_end = remaining[line_start..]
+            .find('\n')
+            .map_or(remaining.len(), |l| line_start + l);
+        let line = remaining[line_start..line_end].trim_end_matches('\r');
+        let content_end = line_start + line.len();
+        let line = if indented {
+            line.trim_start_matches([' ', '\t'])
+        } else {
+            line
+        };
+        if line == delim {
+            return Some(content_end);
+        }
+        if line_end == remaining.len() {
+            return None;
+        }
+        line_start = line_end + 1;
+    }
+}
+
+/// Return the offset in `s` after the first closing delimiter, where
+/// `closing(rest)` returns the length of the delimiter at the start of `rest`.
+fn find_closing(s: &str, closing: impl Fn(&str) -> Option<usize>) -> Option<usize> {
+    s.char_indices()
+        .find_map(|(i, _)| closing(&s[i..]).map(|len| i + len))
+}
@@ -0,0 +1,93 @@
+//! Lexing huge inputs on multiple threads.
+use std::{thread, vec, vec::Vec};
+
+use crate::{Error, ErrorKind, Lexer, Stateless};
+
+type Tokens<'a, T> = Result<Vec<(usize, T, usize)>, Error<<T as Lexer<'a>>::Error>>;
+
+/// Lex `input` in (up to) `chunks` chunks in parallel, one thread per chunk.
+///
+/// `input` is split near evenly spaced offsets, at the first char boundary
+/// `i` after each of them for which `is_sync_point(input, i)` holds. A sync
+/// point must be somewhere lexing can start from scratch, i.e. a token
+/// boundary that isn't inside any token, e.g. the start of a line if no token
+/// spans multiple lines. See [`line_start`].
+///
+/// The tokens of all chunks are returned in order, with offsets relative to
+/// `input`. If there are lexical errors, the first one is returned.
+///
+/// `std::thread::available_parallelism` is a good choice for `chunks`.
+/// Lexers with `#[lexer(intern)]` fields aren't supported, see
+/// [`Stateless`].
+pub fn lex_parallel<'a, T>(
+    input: &'a str,
+    chunks: usize,
+    is_sync_point: impl Fn(&str, usize) -> bool,
+) -> Tokens<'a, T>
+where
+    T: Lexer<'a> + Stateless + Send,
+    T::Error: Send,
+{
+    let splits = split(input, chunks, is_sync_point);
+    let results: Vec<_> = thread::scope(|s| {
+        let handles: Vec<_> = splits
+            .windows(2)
+            .map(|w| {
+                let (start, end) = (w[0], w[1]);
+                let last = end == input.len();
+                s.spawn(move || lex_chunk::<T>(&input[start..end], start, last))
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    let mut tokens =
+        Vec::with_capacity(results.iter().map(|r| r.as_ref().map_or(0, Vec::len)).sum());
+    for r in results {
+        tokens.extend(r?);
+    }
+    Ok(tokens)
+}
+
+/// A sync point for [`lex_parallel`] at the start of every line. Offsets `0`
+/// and past the end of `input` aren't.
+pub fn line_start(input: &str, i: usize) -> bool {
+    i > 0 && input.as_bytes().get(i - 1) == Some(&b'\n')
+}
+
+/// Lex one chunk. Only the last chunk keeps its `#[lexer(eof)]` token, the
+/// only empty token there can be.
+fn lex_chunk<'a, T: Lexer<'a>>(chunk: &'a str, offset: usize, last: bool) -> Tokens<'a, T> {
+    T::lex(chunk)
+        .into_iter()
+        .filter(|r| last || !matches!(r, Ok((l, _, r)) if l == r))
+        .map(|r| match r {
+            Ok((l, t, r)) => Ok((l + offset, t, r + offset)),
+            Err(Error(start, kind)) => Err(Error(
+                start + offset,
+                match kind {
+                    ErrorKind::User { end, error } => ErrorKind::User {
+                        end: end + offset,
+                        error,
+                    },
+                    kind => kind,
+                },
+            )),
+        })
+        .collect()
+}
+
+/// Offsets of the chunks, including `0` and `input.len()`.
+fn split(input: &str, chunks: usize, is_sync_point: impl Fn(&str, usize) -> bool) -> Vec<usize> {
+    let mut splits = vec![0];
+    for k in 1..chunks {
+        let target = input.len() / chunks * k;
+        let from = target.max(splits[splits.len() - 1] + 1);
+        match (from..input.len()).find(|&i| input.is_char_boundary(i) && is_sync_point(input, i)) {
+            Some(i) => splits.push(i),
+            None => break,
+        }
+    }
+    splits.push(input.len());
+    splits
+}
@@ -0,0 +1,81 @@
+//! Lossless syntax trees with rowan.
+//!
+//! [`tokens`] yields a `SyntaxKind` for every piece of the input, including
+//! skipped text and errors, so a rowan tree built from them has exactly the
+//! text of the input. Kinds are numbered:
+//!
+//! - `0..V`: the `V` variants in declaration order.
+//! - `V..V + S`: the `S` `skip` patterns in order.
+//! - `V + S`: text skipped by the callback of a variant returning
+//!   `More::Skip`.
+//! - `V + S + 1`: text that couldn't be lexed.
+//!
+//! Kinds from [`kind_count`] on are free for nodes.
+//!
+//! ```
+//! # use lexi_matic::{rowan, Lexer};
+//! # use ::rowan::GreenNodeBuilder;
+//! #[derive(Debug, Lexer)]
+//! #[lexer(skip = r"[ \t\r\n\f]+")]
+//! enum Token {
+//!     #[regex("[a-z]+")]
+//!     Ident,
+//! }
+//!
+//! let mut builder = GreenNodeBuilder::new();
+//! builder.start_node(rowan::kind_count::<Token>());
+//! for (kind, text) in rowan::tokens::<Token>("foo ? bar") {
+//!     builder.token(kind, text);
+//! }
+//! builder.finish_node();
+//! let root = builder.finish();
+//! assert_eq!(root.to_string(), "foo ? bar");
+//! ```
+use ::rowan::SyntaxKind;
+
+use crate::{Lossless, Piece};
+
+/// The kind of a piece of the input.
+pub fn kind<'a, T: Lossless<'a>>(piece: &Piece<T, T::Error>) -> SyntaxKind {
+    let variants = T::VARIANTS.len();
+    let skips = T::SKIP_LABELS.len();
+    let kind = match piece {
+        Piece::Token(t) => t.variant_index(),
+        Piece::Skip(Some(k)) => variants + k,
+        Piece::Skip(None) => variants + skips,
+        Piece::Error(_) => variants + skips + 1,
+    };
+    SyntaxKind(kind as u16)
+}
+
+/// The number of token kinds, i.e. the first kind for nodes.
+pub fn kind_count<'a, T: Lossless<'a>>() -> SyntaxKind {
+    SyntaxKind((T::VARIANTS.len() + T::SKIP_LABELS.len() + 2) as u16)
+}
+
+/// The variant name or skip label of a token kind, e.g. for `Debug` output
+/// of a `rowan::Language`. `None` for node kinds.
+pub fn kind_name<'a, T: Lossless<'a>>(kind: SyntaxKind) -> Option<&'static str> {
+    let kind = usize::from(kind.0);
+    let variants = T::VARIANTS.len();
+    let skips = T::SKIP_LABELS.len();
+    if kind < variants {
+        Some(T::VARIANTS[kind])
+    } else if kind < variants + skips {
+        Some(T::SKIP_LABELS[kind - variants])
+    } else if kind == variants + skips {
+        Some("skip")
+    } else if kind == variants + skips + 1 {
+        Some("error")
+    } else {
+        None
+    }
+}
+
+/// The pieces of `input` with their kinds. The texts concatenate to `input`.
+/// The empty `#[lexer(eof)]` token is left out.
+pub fn tokens<'a, T: Lossless<'a>>(input: &'a str) -> impl Iterator<Item = (SyntaxKind, &'a str)> {
+    T::lex_lossless(input)
+        .filter(|(start, _, end)| start < end)
+        .map(move |(start, piece, end)| (kind(&piece), &input[start..end]))
+}
@@ -0,0 +1,151 @@
+//! LSP semantic tokens.
+//!
+//! Variants and `skip` patterns are classified with
+//! `#[lexer(semantic = "type")]` or `semantic = "type.modifier1.modifier2"`,
+//! like VS Code's semantic token selectors. Lexers with such attributes
+//! implement [`Semantic`], which has the legend, and [`semantic_tokens`] encodes a document:
+//!
+//! ```
+//! # #[cfg(feature = "dfa")] {
+//! # use lexi_matic::{semantic::{semantic_tokens, Semantic}, Lexer};
+//! #[derive(Debug, Lexer)]
+//! #[lexer(
+//!     skip = r"[ \t\r\n\f]+",
+//!     skip("//[^\n]*", semantic = "comment"),
+//! )]
+//! enum Token {
+//!     #[token("let")]
+//!     #[lexer(semantic = "keyword")]
+//!     Let,
+//!     #[token("=")]
+//!     Eq,
+//!     #[regex("[a-z]+")]
+//!     #[lexer(semantic = "variable.declaration")]
+//!     Ident,
+//! }
+//!
+//! assert_eq!(Token::TOKEN_TYPES, ["keyword", "variable", "comment"]);
+//! assert_eq!(Token::TOKEN_MODIFIERS, ["declaration"]);
+//! let data: Vec<u32> = semantic_tokens::<Token>("let x = y // z\nlet")
+//!     .flatten()
+//!     .collect();
+//! assert_eq!(
+//!     data,
+//!     [0, 0, 3, 0, 0, 0, 4, 1, 1, 1, 0, 4, 1, 1, 1, 0, 2, 4, 2, 0, 1, 0, 3, 0, 0],
+//! );
+//! # }
+//! ```
+use crate::{Lossless, Piece};
+
+/// Semantic token classification, implemented by the derive for lexers with
+/// `semantic` attributes.
+pub trait Semantic<'a>: Lossless<'a> {
+    /// The token types of the legend.
+    const TOKEN_TYPES: &'static [&'static str];
+    /// The token modifiers of the legend.
+    const TOKEN_MODIFIERS: &'static [&'static str];
+    /// The token type index and modifier bits of the variants in declaration
+    /// order, `None` for unclassified ones.
+    const VARIANT_SEMANTICS: &'static [Option<(u32, u32)>];
+    /// The same for the `skip` patterns.
+    const SKIP_SEMANTICS: &'static [Option<(u32, u32)>];
+
+    /// The token type index and modifier bits of a piece of the input.
+    fn semantic(piece: &Piece<Self, Self::Error>) -> Option<(u32, u32)> {
+        match piece {
+            Piece::Token(t) => Self::VARIANT_SEMANTICS[t.variant_index()],
+            Piece::Skip(Some(k)) => Self::SKIP_SEMANTICS[*k],
+            Piece::Skip(None) | Piece::Error(_) => None,
+        }
+    }
+}
+
+/// Encode the classified tokens of a document as LSP semantic tokens: `[delta
+/// line, delta start, length, token type, token modifiers]`, with the start
+/// and length in UTF-16 code units. Flatten them for `SemanticTokens::data`.
+///
+/// Tokens spanning multiple lines are split into one token per line, for
+/// clients without multiline token support. Lexing continues after errors.
+pub fn semantic_tokens<'a, T: Semantic<'a>>(input: &'a str) -> SemanticTokens<'a, T> {
+    SemanticTokens {
+        input,
+        pieces: T::lex_lossless(input),
+        current: None,
+        line: 0,
+        col: 0,
+        after_cr: false,
+        prev_line: 0,
+        prev_col: 0,
+    }
+}
+
+/// Iterator returned by [`semantic_tokens`].
+pub struct SemanticTokens<'a, T: Semantic<'a>> {
+    input: &'a str,
+    pieces: T::LosslessIterator,
+    /// The rest of a classified piece that spans multiple lines.
+    current: Option<(&'a str, u32, u32)>,
+    /// Position after the text so far.
+    line: u32,
+    col: u32,
+    /// Whether the text so far ends with `\r`, so that a following `\n`
+    /// doesn't start another line.
+    after_cr: bool,
+    /// Position of the last token.
+    prev_line: u32,
+    prev_col: u32,
+}
+
+impl<'a, T: Semantic<'a>> SemanticTokens<'a, T> {
+    fn advance(&mut self, text: &str) {
+        for c in text.chars() {
+            match c {
+                '\n' if self.after_cr => {}
+                '\n' | '\r' => {
+                    self.line += 1;
+                    self.col = 0;
+                }
+                _ => self.col += c.len_utf16() as u32,
+            }
+            self.after_cr = c == '\r';
+        }
+    }
+}
+
+impl<'a, T: Semantic<'a>> Iterator for SemanticTokens<'a, T> {
+    type Item = [u32; 5];
+
+    fn next(&mut self) -> Option<[u32; 5]> {
+        loop {
+            let Some((text, ty, modifiers)) = self.current.take() else {
+                let (start, piece, end) = self.pieces.next()?;
+                let text = &self.input[start..end];
+                match T::semantic(&piece) {
+                    Some((ty, modifiers)) => self.current = Some((text, ty, modifiers)),
+                    None => self.advance(text),
+                }
+                continue;
+            };
+            // The first line of the piece, including its line break.
+            let end = text.find(['\n', '\r']).map_or(text.len(), |i| i + 1);
+            let (line, rest) = text.split_at(end);
+            if !rest.is_empty() {
+                self.current = Some((rest, ty, modifiers));
+            }
+            let (l, c) = (self.line, self.col);
+            self.advance(line);
+            let len = line.trim_end_matches(['\n', '\r']).encode_utf16().count() as u32;
+            if len > 0 {
+                let delta_line = l - self.prev_line;
+                let delta_start = if delta_line == 0 {
+                    c - self.prev_col
+                } else {
+                    c
+                };
+                self.prev_line = l;
+                self.prev_col = c;
+                return Some([delta_line, delta_start, len, ty, modifiers]);
+            }
+        }
+    }
+}
@@ -0,0 +1,300 @@
+//! Golden file tests for lexers.
+//!
+//! [`render`] writes a token stream in a stable textual format, one token per
+//! line as `Kind "text" @start..end`, and [`assert_snapshot_file`] compares it
+//! with a `.tokens` file next to the input:
+//!
+//! ```no_run
+//! # use lexi_matic::Lexer;
+//! #[derive(Debug, Lexer)]
+//! #[lexer(skip = r"[ \t\r\n]+")]
+//! enum Token<'a> {
+//!     #[regex("[a-z]+")]
+//!     Ident(&'a str),
+//! }
+//!
+//! #[test]
+//! fn test_golden() {
+//!     // Compares with `tests/golden/hello.tokens`.
+//!     lexi_matic::testing::assert_snapshot_file::<Token>("tests/golden/hello.txt");
+//! }
+//! ```
+//!
+//! Run the tests with `LEXI_MATIC_BLESS=1` to create or update the `.tokens`
+//! files instead.
+//!
+//! [`check_random`] lexes random inputs, including ones made of strings
+//! matching the lexer's regexes, and panics with a minimal failing input if
+//! the spans or callback results are wrong, see [`check_invariants`]. The
+//! number of cases can be set with the `PROPTEST_CASES` environment variable.
+//!
+//! ```
+//! # use lexi_matic::Lexer;
+//! #[derive(Debug, Lexer)]
+//! #[lexer(skip = r"[ \t\r\n]+")]
+//! enum Token<'a> {
+//!     #[regex("[a-z]+")]
+//!     Ident(&'a str),
+//!     #[regex(r#""[^"]*""#)]
+//!     String(&'a str),
+//! }
+//!
+//! lexi_matic::testing::check_random::<Token>();
+//! ```
+use std::{
+    env,
+    fmt::{Debug, Write},
+    fs, io,
+    path::Path,
+    string::String,
+    vec,
+    vec::Vec,
+};
+
+use proptest::{collection, prelude::*, string::string_regex, test_runner::TestRunner};
+
+use crate::{Error, ErrorKind, Lossless, Piece};
+
+/// The environment variable that makes assertions write the snapshots.
+pub const BLESS_VAR: &str = "LEXI_MATIC_BLESS";
+
+/// Render the tokens and errors of `input`, one per line, as
+/// `Kind "text" @start..end` with the variant name and the escaped source.
+/// Errors are rendered as `error` (with the callback's error in parentheses
+/// for `User` errors), and lexing continues after them. Skipped text isn't
+/// rendered.
+///
+/// ```
+/// # use lexi_matic::Lexer;
+/// #[derive(Debug, Lexer)]
+/// #[lexer(skip = " +")]
+/// enum Token {
+///     #[token("if")]
+///     If,
+///     #[regex("[a-z]+")]
+///     Ident,
+/// }
+///
+/// assert_eq!(
+///     lexi_matic::testing::render::<Token>("if x ?"),
+///     "If \"if\" @0..2\nIdent \"x\" @3..4\nerror \"?\" @5..6\n",
+/// );
+/// ```
+pub fn render<'a, T>(input: &'a str) -> String
+where
+    T: Lossless<'a>,
+    T::Error: Debug,
+{
+    let mut out = String::new();
+    for (start, piece, end) in T::lex_lossless(input) {
+        let text = &input[start..end];
+        match piece {
+            Piece::Token(t) => out.push_str(T::VARIANTS[t.variant_index()]),
+            Piece::Skip(_) => continue,
+            Piece::Error(ErrorKind::NoMatch) => out.push_str("error"),
+            Piece::Error(ErrorKind::User { error, .. }) => write!(out, "error({error:?})").unwrap(),
+            Piece::Error(ErrorKind::Callback { pattern, len }) => {
+                write!(out, "error(callback of {pattern} returned {len})").unwrap()
+            }
+        }
+        writeln!(out, " {text:?} @{start}..{end}").unwrap();
+    }
+    out
+}
+
+/// Assert that [`render`]ing `input` gives the contents of the file `golden`.
+///
+/// If the `LEXI_MATIC_BLESS` environment variable is set (and not `0`), the
+/// file is written instead.
+///
+/// # Panics
+///
+/// If the rendering differs, with a line diff, or if the file can't be read.
+#[track_caller]
+pub fn assert_snapshot<'a, T>(input: &'a str, golden: impl AsRef<Path>)
+where
+    T: Lossless<'a>,
+    T::Error: Debug,
+{
+    let golden = golden.as_ref();
+    let actual = render::<T>(input);
+    if bless() {
+        if fs::read_to_string(golden).ok().as_deref() != Some(&*actual) {
+            fs::write(golden, &actual)
+                .unwrap_or_else(|e| panic!("writing {}: {e}", golden.display()));
+        }
+        return;
+    }
+    let expected = match fs::read_to_string(golden) {
+        Ok(expected) => expected,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => panic!(
+            "snapshot {} doesn't exist, run with {BLESS_VAR}=1 to create it",
+            golden.display()
+        ),
+        Err(e) => panic!("reading {}: {e}", golden.display()),
+    };
+    if expected != actual {
+        panic!(
+            "snapshot {} differs (-expected +actual), run with {BLESS_VAR}=1 to update it:\n{}",
+            golden.display(),
+            diff(&expected, &actual)
+        );
+    }
+}
+
+/// [`assert_snapshot`] for the contents of the file `input`, with the
+/// snapshot in the file next to it with the extension `tokens`.
+///
+/// The input is leaked so that tokens can borrow from it, which is fine in
+/// tests.
+#[track_caller]
+pub fn assert_snapshot_file<T>(input: impl AsRef<Path>)
+where
+    T: Lossless<'static>,
+    T::Error: Debug,
+{
+    let input = input.as_ref();
+    let source =
+        fs::read_to_string(input).unwrap_or_else(|e| panic!("reading {}: {e}", input.display()));
+    assert_snapshot::<T>(source.leak(), input.with_extension("tokens"));
+}
+
+/// Assert the invariants of lexing `input`:
+///
+/// - The pieces yielded by `lex_lossless` are in order, start and end at
+///   char boundaries, and cover the whole input without gaps or overlap.
+/// - `lex` yields the same tokens with the same spans up to the first error,
+///   which is the first error piece.
+///
+/// - No `more` or `callback` function returns a length that is out of range
+///   or not at a char boundary, i.e. there are no `ErrorKind::Callback`
+///   errors.
+///
+/// # Panics
+///
+/// If an invariant doesn't hold, with the offending span.
+#[track_caller]
+pub fn check_invariants<'a, T>(input: &'a str)
+where
+    T: Lossless<'a>,
+    T::Error: Debug,
+{
+    let mut tokens = Vec::new();
+    let mut first_error = None;
+    let mut consumed = 0;
+    for (start, piece, end) in T::lex_lossless(input) {
+        assert_eq!(start, consumed, "gap or overlap at {start}..{end}");
+        assert!(
+            start <= end && end <= input.len(),
+            "bad span {start}..{end}"
+        );
+        assert!(
+            input.is_char_boundary(start) && input.is_char_boundary(end),
+            "span {start}..{end} isn't at char boundaries"
+        );
+        if let Piece::Error(ErrorKind::Callback { pattern, len }) = piece {
+            panic!("callback of {pattern} returned invalid token length {len} at {start}");
+        }
+        consumed = end;
+        if first_error.is_none() {
+            match piece {
+                Piece::Token(t) => tokens.push((start, t.variant_index(), end)),
+                Piece::Skip(_) => {}
+                Piece::Error(_) => first_error = Some(start),
+            }
+        }
+    }
+    assert_eq!(consumed, input.len(), "pieces end at {consumed}");
+
+    let mut lexed = Vec::new();
+    for r in T::lex(input) {
+        match r {
+            Ok((start, t, end)) => lexed.push((start, t.variant_index(), end)),
+            Err(Error(start, _)) => {
+                assert_eq!(Some(start), first_error, "lex error at {start}");
+                break;
+            }
+        }
+    }
+    assert_eq!(lexed, tokens, "lex and lex_lossless yield different tokens");
+}
+
+/// Inputs for property tests: up to 8 pieces that are either strings matching
+/// one of `T::REGEXES` or a few arbitrary chars, concatenated.
+pub fn inputs<'a, T: Lossless<'a>>() -> BoxedStrategy<String> {
+    let mut pieces: Vec<BoxedStrategy<String>> = T::REGEXES
+        .iter()
+        .filter_map(|r| string_regex(r).ok())
+        .map(|s| s.boxed())
+        .collect();
+    pieces.push(
+        collection::vec(any::<char>(), 0..4)
+            .prop_map(String::from_iter)
+            .boxed(),
+    );
+    collection::vec(proptest::strategy::Union::new(pieces), 0..8)
+        .prop_map(|pieces| pieces.concat())
+        .boxed()
+}
+
+/// Run [`check_invariants`] on random [`inputs`].
+///
+/// The inputs are leaked so that tokens can borrow from them, which is fine
+/// in tests.
+///
+/// # Panics
+///
+/// With the minimal input violating an invariant.
+#[track_caller]
+pub fn check_random<T>()
+where
+    T: Lossless<'static>,
+    T::Error: Debug,
+{
+    let result = TestRunner::default().run(&inputs::<T>(), |input| {
+        check_invariants::<T>(input.leak());
+        Ok(())
+    });
+    if let Err(e) = result {
+        panic!("{e}");
+    }
+}
+
+fn bless() -> bool {
+    env::var_os(BLESS_VAR).is_some_and(|v| !v.is_empty() && v != "0")
+}
+
+/// A line diff of `a` and `b` from their longest common subsequence, with
+/// unchanged lines prefixed with a space, removed ones with `-` and added
+/// ones with `+`.
+fn diff(a: &str, b: &str) -> String {
+    let a: Vec<_> = a.lines().collect();
+    let b: Vec<_> = b.lines().collect();
+    // lcs[i][j] is the length of the LCS of a[i..] and b[j..].
+    let mut lcs = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let mut out = String::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            writeln!(out, " {}", a[i]).unwrap();
+            i += 1;
+            j += 1;
+        } else if i < a.len() && (j == b.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            writeln!(out, "-{}", a[i]).unwrap();
+            i += 1;
+        } else {
+            writeln!(out, "+{}", b[j]).unwrap();
+            j += 1;
+        }
+    }
+    out
+}
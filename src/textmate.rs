@@ -0,0 +1,206 @@
+//! TextMate and Sublime Text grammars for syntax highlighting.
+//!
+//! Variants and `skip` patterns get a scope with `#[lexer(scope = "...")]`
+//! or `skip("...", scope = "...")`, and [`tm_language`] and [`sublime_syntax`]
+//! generate a grammar from all patterns of a lexer, e.g. from a build script
+//! or a test that keeps a checked in grammar up to date:
+//!
+//! ```
+//! # #[cfg(feature = "alloc")] {
+//! # use lexi_matic::{textmate, Lexer};
+//! #[derive(Debug, Lexer)]
+//! #[lexer(skip = r"[ \t\r\n\f]+", skip("#[^\n]*", scope = "comment.line"))]
+//! enum Token {
+//!     #[token("if")]
+//!     #[lexer(scope = "keyword.control")]
+//!     If,
+//!     #[regex("[a-z]+")]
+//!     Ident,
+//! }
+//!
+//! let grammar = textmate::tm_language::<Token>("Example", "source.example", &["ex"]).unwrap();
+//! assert!(grammar.contains(r#""match": "if\\b""#));
+//! # }
+//! ```
+//!
+//! Patterns are translated to the Oniguruma syntax of TextMate, and
+//! generating fails for patterns it can't express. Scoped patterns also must
+//! not have callbacks or match line breaks other than at the end, because
+//! TextMate matches one line at a time. Unscoped patterns like that are left
+//! out of the grammar, as a rule for them would match something else, e.g.
+//! just the opener of a comment with a `more` callback.
+//!
+//! TextMate takes the first pattern matching at the leftmost position rather
+//! than the longest match, so word-like `#[token]`s get a trailing `\b` to not
+//! match prefixes of identifiers. Other patterns that are prefixes of later
+//! ones may need reordering.
+#[cfg(feature = "alloc")]
+use alloc::{format, string::String};
+use core::fmt;
+
+/// A variant or `skip` pattern of a lexer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    /// The variant name or skip label.
+    pub name: &'static str,
+    pub skip: bool,
+    pub scope: Option<&'static str>,
+    /// Whether it has a `more` or `callback` function.
+    pub callback: bool,
+    /// Whether it can match a line break before its last char.
+    pub spans_lines: bool,
+    /// The regex in Oniguruma syntax, or the feature it can't express.
+    pub oniguruma: Result<&'static str, &'static str>,
+}
+
+/// The patterns of a lexer, implemented by the derive for lexers with `scope`
+/// attributes.
+pub trait Grammar {
+    /// Variants in declaration order, then `skip` patterns.
+    const PATTERNS: &'static [Pattern];
+}
+
+/// Why a pattern can't be in a grammar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrammarError {
+    /// The variant name or skip label.
+    pub pattern: &'static str,
+    pub kind: GrammarErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GrammarErrorKind {
+    /// The regex uses this feature.
+    Unsupported(&'static str),
+    /// A scoped pattern can match multiple lines.
+    SpansLines,
+    /// A scoped pattern has a `more` or `callback` function.
+    Callback,
+}
+
+impl fmt::Display for GrammarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pattern `{}` ", self.pattern)?;
+        match self.kind {
+            GrammarErrorKind::Unsupported(feature) => {
+                write!(f, "uses an unsupported regex feature: {feature}")
+            }
+            GrammarErrorKind::SpansLines => {
+                f.write_str("can match multiple lines, but TextMate matches one line at a time")
+            }
+            GrammarErrorKind::Callback => f.write_str("has a callback TextMate can't run"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for GrammarError {}
+
+/// The Oniguruma regex and scope of each pattern, without unscoped ones
+/// TextMate can't match.
+#[cfg(feature = "alloc")]
+fn rules<T: Grammar>(
+) -> impl Iterator<Item = Result<(&'static str, Option<&'static str>), GrammarError>> {
+    T::PATTERNS
+        .iter()
+        .filter(|p| p.scope.is_some() || !(p.callback || p.spans_lines))
+        .map(|p| {
+            let error = |kind| GrammarError {
+                pattern: p.name,
+                kind,
+            };
+            let regex = p
+                .oniguruma
+                .map_err(|e| error(GrammarErrorKind::Unsupported(e)))?;
+            if p.spans_lines {
+                return Err(error(GrammarErrorKind::SpansLines));
+            }
+            if p.callback {
+                return Err(error(GrammarErrorKind::Callback));
+            }
+            Ok((regex, p.scope))
+        })
+}
+
+/// Generate a `.tmLanguage.json` grammar.
+#[cfg(feature = "alloc")]
+pub fn tm_language<T: Grammar>(
+    name: &str,
+    scope_name: &str,
+    file_types: &[&str],
+) -> Result<String, GrammarError> {
+    let mut out = format!(
+        "{{\n  \"name\": {},\n  \"scopeName\": {},\n  \"fileTypes\": [",
+        json_string(name),
+        json_string(scope_name),
+    );
+    for (i, t) in file_types.iter().enumerate() {
+        if i > 0 {
+            out.push_str(", ");
+        }
+        out.push_str(&json_string(t));
+    }
+    out.push_str("],\n  \"patterns\": [");
+    for (i, rule) in rules::<T>().enumerate() {
+        let (regex, scope) = rule?;
+        out.push_str(if i > 0 { ",\n    {\n" } else { "\n    {\n" });
+        if let Some(scope) = scope {
+            out.push_str(&format!("      \"name\": {},\n", json_string(scope)));
+        }
+        out.push_str(&format!("      \"match\": {}\n    }}", json_string(regex)));
+    }
+    out.push_str("\n  ]\n}\n");
+    Ok(out)
+}
+
+/// Generate a `.sublime-syntax` grammar.
+#[cfg(feature = "alloc")]
+pub fn sublime_syntax<T: Grammar>(
+    name: &str,
+    scope: &str,
+    file_extensions: &[&str],
+) -> Result<String, GrammarError> {
+    let mut out = format!(
+        "%YAML 1.2\n---\nname: {}\nscope: {}\nfile_extensions: [",
+        yaml_string(name),
+        yaml_string(scope),
+    );
+    for (i, e) in file_extensions.iter().enumerate() {
+        if i > 0 {
+            out.push_str(", ");
+        }
+        out.push_str(&yaml_string(e));
+    }
+    out.push_str("]\ncontexts:\n  main:\n");
+    for rule in rules::<T>() {
+        let (regex, scope) = rule?;
+        out.push_str(&format!("    - match: {}\n", yaml_string(regex)));
+        if let Some(scope) = scope {
+            out.push_str(&format!("      scope: {}\n", yaml_string(scope)));
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(feature = "alloc")]
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", u32::from(c))),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// A single quoted YAML string. Line breaks don't occur in our strings.
+#[cfg(feature = "alloc")]
+fn yaml_string(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
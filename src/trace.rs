@@ -0,0 +1,122 @@
+//! Tracing the token stream for debugging.
+//!
+//! [`Lossless::trace`] lexes like `lex` and writes a line for every token,
+//! skipped text and error to a `fmt::Write`, e.g. a `String` or, with the
+//! `log` feature, [`Log`]:
+//!
+//! ```
+//! # #[cfg(feature = "dfa")] {
+//! # use lexi_matic::{Lexer, Lossless};
+//! #[derive(Debug, Lexer)]
+//! #[lexer(skip(r"[ \t\n]+", label = "space"))]
+//! enum Token<'a> {
+//!     #[regex("[a-z]+")]
+//!     Ident(&'a str),
+//! }
+//!
+//! let mut trace = String::new();
+//! let tokens = Token::trace("foo\n  bar", &mut trace).count();
+//! assert_eq!(tokens, 2);
+//! assert_eq!(
+//!     trace,
+//!     "1:1 0..3 Ident(\"foo\") \"foo\"\n\
+//!      1:4 3..6 skip space \"\\n  \"\n\
+//!      2:3 6..9 Ident(\"bar\") \"bar\"\n",
+//! );
+//! # }
+//! ```
+use core::fmt::{Debug, Write};
+
+use crate::{Error, Lossless, Piece};
+
+/// Iterator returned by [`Lossless::trace`].
+pub struct Trace<'a, T: Lossless<'a>, W> {
+    input: &'a str,
+    pieces: T::LosslessIterator,
+    out: W,
+    /// 1-based line and column in chars of the next piece.
+    line: usize,
+    column: usize,
+}
+
+impl<'a, T: Lossless<'a>, W> Trace<'a, T, W> {
+    pub(crate) fn new(input: &'a str, out: W) -> Self {
+        Trace {
+            input,
+            pieces: T::lex_lossless(input),
+            out,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    /// The writer.
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+impl<'a, T, W> Iterator for Trace<'a, T, W>
+where
+    T: Lossless<'a> + Debug,
+    T::Error: Debug,
+    W: Write,
+{
+    type Item = Result<(usize, T, usize), Error<T::Error>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (start, piece, end) = self.pieces.next()?;
+            let text = &self.input[start..end];
+            // Errors writing the trace are ignored, like those of `eprintln!`
+            // aren't reported.
+            let _ = write!(self.out, "{}:{} {start}..{end} ", self.line, self.column);
+            let _ = match &piece {
+                Piece::Token(t) => write!(self.out, "{t:?}"),
+                Piece::Skip(Some(k)) => {
+                    write!(self.out, "skip {}", T::SKIP_LABELS[*k].escape_debug())
+                }
+                Piece::Skip(None) => write!(self.out, "skip"),
+                Piece::Error(kind) => write!(self.out, "error {kind:?}"),
+            };
+            let _ = writeln!(self.out, " {text:?}");
+            for c in text.chars() {
+                if c == '\n' {
+                    self.line += 1;
+                    self.column = 1;
+                } else {
+                    self.column += 1;
+                }
+            }
+            match piece {
+                Piece::Token(t) => return Some(Ok((start, t, end))),
+                Piece::Skip(_) => {}
+                Piece::Error(kind) => return Some(Err(Error(start, kind))),
+            }
+        }
+    }
+}
+
+/// A `fmt::Write` that logs each line at trace level with the `log` crate.
+#[cfg(feature = "log")]
+#[derive(Debug, Default)]
+pub struct Log {
+    line: alloc::string::String,
+}
+
+#[cfg(feature = "log")]
+impl Write for Log {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        for part in s.split_inclusive('\n') {
+            match part.strip_suffix('\n') {
+                Some(rest) => {
+                    self.line.push_str(rest);
+                    log::trace!(target: "lexi_matic", "{}", self.line);
+                    self.line.clear();
+                }
+                None => self.line.push_str(part),
+            }
+        }
+        Ok(())
+    }
+}
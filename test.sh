@@ -2,4 +2,10 @@ set -e
 
 cargo fmt
 cargo test --all
+cargo test --all-features
+cargo test --no-default-features --features dfa
+# Native code generation only, which runs tests/native.rs.
+cargo test --no-default-features
 cargo clippy --all --all-targets
+# The generated code must build without std.
+cargo build -p lexi-matic-no-std-test
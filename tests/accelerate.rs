@@ -0,0 +1,57 @@
+#![cfg(feature = "dfa")]
+use lexi_matic::Lexer;
+
+#[macro_use]
+mod common;
+
+use common::lex;
+
+backends! {
+    #[lexer(skip = "//[^\n]*", skip = r"/\*([^*]|\*+[^*/])*\*+/", skip = r"[ \t\r\n\f]+")]
+    enum<'a> {
+        #[token("import")]
+        Import,
+        #[token(";")]
+        Semi,
+        #[regex("[a-zA-Z_][a-zA-Z0-9_]*")]
+        Ident(&'a str),
+        #[regex(r#""[^"]*""#)]
+        Str(&'a str),
+        #[regex(r"#.*é")]
+        HashE(&'a str),
+        #[regex(r"\n\t+")]
+        NewlineTabs(&'a str),
+    }
+}
+
+#[test]
+fn test_same_as_plain() {
+    let inputs = [
+        "import foo; // a comment with ünïcödé\nimport bar;",
+        "// comment at the end without newline ✓",
+        "/* block\n comment ** ☃ */ import /* unterminated",
+        "\"a string with ☃ and // inside\" ; \"unterminated ✓",
+        "# hash é # hash ☃ é é\n# no e",
+        "   \t\t\n\n    \n\t\tfoo",
+        "foo\n\t\tbar\n        baz",
+        "",
+    ];
+    for input in inputs {
+        let plain = lex::<Plain>(input);
+        assert_eq!(plain, lex::<Accelerated>(input), "{input:?}");
+        assert_eq!(plain, lex::<Native>(input), "{input:?}");
+    }
+}
+
+#[test]
+fn test_tokens() {
+    let input = "import /* ☃ */ foo; // ✓\n\t\"s\"";
+    let expected = [
+        (0, Accelerated::Import, 6),
+        (17, Accelerated::Ident("foo"), 20),
+        (20, Accelerated::Semi, 21),
+        (28, Accelerated::NewlineTabs("\n\t"), 30),
+        (30, Accelerated::Str("\"s\""), 33),
+    ];
+    itertools::assert_equal(Accelerated::lex(input).map(Result::unwrap), expected);
+}
@@ -0,0 +1,201 @@
+#![cfg(feature = "alloc")]
+use lexi_matic::{buffer::TokenBuffer, Lexer};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Lexer)]
+#[lexer(skip = r"[ \t\r\n\f]+")]
+enum Token<'a> {
+    #[token("+")]
+    Plus,
+    #[token("(")]
+    LParen,
+    #[token(")")]
+    RParen,
+    #[regex("[0-9]+")]
+    Num(&'a str),
+}
+
+#[test]
+fn test_buffer() {
+    let buffer = TokenBuffer::<Token>::lex(" 1 + (2)  ").unwrap();
+    assert_eq!(buffer.len(), 5);
+    assert_eq!(buffer.input_len(), 10);
+    assert_eq!(buffer[1].token, Token::Plus);
+    assert_eq!(buffer[1].span(), 3..4);
+    assert_eq!(buffer.span(2..5), 5..8);
+    assert_eq!(buffer.span(2..2), 5..5);
+    assert_eq!(buffer.span(5..5), 10..10);
+    assert_eq!(buffer.tokens().offset(), 1);
+
+    let error = TokenBuffer::<Token>::lex("1 + $").unwrap_err();
+    assert_eq!(error.0, 4);
+}
+
+#[cfg(feature = "chumsky")]
+mod with_chumsky {
+    use chumsky::prelude::*;
+
+    use super::*;
+
+    fn sum<'a>(
+    ) -> impl Parser<'a, &'a TokenBuffer<Token<'a>>, u64, extra::Err<Simple<'a, Token<'a>>>> {
+        recursive(|sum| {
+            let atom = select! { Token::Num(n) => n.parse::<u64>().unwrap() }
+                .or(sum.delimited_by(just(Token::LParen), just(Token::RParen)));
+            atom.clone()
+                .foldl(just(Token::Plus).ignore_then(atom).repeated(), |a, b| a + b)
+        })
+    }
+
+    #[test]
+    fn test_chumsky() {
+        let buffer = TokenBuffer::lex("1 + (2 + 3)").unwrap();
+        assert_eq!(sum().parse(&buffer).into_result(), Ok(6));
+
+        let buffer = TokenBuffer::lex("1 +  + 2").unwrap();
+        let errors = sum().parse(&buffer).into_errors();
+        assert_eq!(errors[0].span().into_range(), 5..6);
+
+        let buffer = TokenBuffer::lex("(1 + 2 ").unwrap();
+        let errors = sum().parse(&buffer).into_errors();
+        assert_eq!(errors[0].span().into_range(), 7..7);
+
+        let buffer = TokenBuffer::lex(" 1 + 2 ").unwrap();
+        let spanned = sum()
+            .map_with(|n, e| (n, e.span().into_range()))
+            .parse(&buffer);
+        assert_eq!(spanned.into_result(), Ok((3, 1..6)));
+    }
+}
+
+#[cfg(feature = "winnow")]
+mod with_winnow {
+    use winnow::{
+        combinator::{alt, delimited, preceded, repeat},
+        error::ContextError,
+        prelude::*,
+        stream::{Location, TokenSlice},
+        token::any,
+    };
+
+    use super::*;
+    use lexi_matic::buffer::Spanned;
+
+    type Input<'i, 'a> = TokenSlice<'i, Spanned<Token<'a>>>;
+
+    fn num(input: &mut Input<'_, '_>) -> ModalResult<u64> {
+        any.verify_map(|t: &Spanned<Token>| match t.token {
+            Token::Num(n) => n.parse().ok(),
+            _ => None,
+        })
+        .parse_next(input)
+    }
+
+    fn token<'i, 'a: 'i>(
+        t: Token<'a>,
+    ) -> impl ModalParser<Input<'i, 'a>, &'i Spanned<Token<'a>>, ContextError> {
+        any.verify(move |s: &Spanned<Token>| s.token == t)
+    }
+
+    fn atom(input: &mut Input<'_, '_>) -> ModalResult<u64> {
+        alt((
+            num,
+            delimited(token(Token::LParen), sum, token(Token::RParen)),
+        ))
+        .parse_next(input)
+    }
+
+    fn sum(input: &mut Input<'_, '_>) -> ModalResult<u64> {
+        let first = atom(input)?;
+        repeat(0.., preceded(token(Token::Plus), atom))
+            .fold(move || first, |a, b| a + b)
+            .parse_next(input)
+    }
+
+    #[test]
+    fn test_winnow() {
+        let buffer = TokenBuffer::lex("1 + (2 + 3)").unwrap();
+        assert_eq!(sum.parse(buffer.stream()), Ok(6));
+
+        let buffer = TokenBuffer::lex("1 + (2 +) + 3").unwrap();
+        let mut stream = buffer.stream();
+        assert_eq!(sum.parse_next(&mut stream), Ok(1));
+        assert_eq!(stream.current_token_start(), 2);
+
+        let (n, span) = sum
+            .with_span()
+            .parse(buffer_of(" 1 + 2 ").stream())
+            .unwrap();
+        assert_eq!((n, span), (3, 1..6));
+    }
+
+    fn buffer_of(input: &'static str) -> TokenBuffer<Token<'static>> {
+        TokenBuffer::lex(input).unwrap()
+    }
+}
+
+#[cfg(feature = "nom")]
+mod with_nom {
+    use nom::{
+        bytes::complete::take,
+        combinator::{map_opt, verify},
+        multi::fold,
+        sequence::{delimited, preceded},
+        IResult, Parser,
+    };
+
+    use super::*;
+    use lexi_matic::buffer::Tokens;
+
+    type Input<'i, 'a> = Tokens<'i, Token<'a>>;
+
+    fn token<'i, 'a: 'i>(
+        t: Token<'a>,
+    ) -> impl Parser<Input<'i, 'a>, Output = Input<'i, 'a>, Error = ::nom::error::Error<Input<'i, 'a>>>
+    {
+        verify(take(1usize), move |s: &Input| s[0].token == t)
+    }
+
+    fn num<'i, 'a>(input: Input<'i, 'a>) -> IResult<Input<'i, 'a>, u64> {
+        map_opt(take(1usize), |s: Input| match s[0].token {
+            Token::Num(n) => n.parse().ok(),
+            _ => None,
+        })
+        .parse(input)
+    }
+
+    fn atom<'i, 'a>(input: Input<'i, 'a>) -> IResult<Input<'i, 'a>, u64> {
+        num.or(delimited(token(Token::LParen), sum, token(Token::RParen)))
+            .parse(input)
+    }
+
+    fn sum<'i, 'a>(input: Input<'i, 'a>) -> IResult<Input<'i, 'a>, u64> {
+        let (input, first) = atom(input)?;
+        fold(
+            0..,
+            preceded(token(Token::Plus), atom),
+            move || first,
+            |a, b| a + b,
+        )
+        .parse(input)
+    }
+
+    #[test]
+    fn test_nom() {
+        let buffer = TokenBuffer::lex("1 + (2 + 3)").unwrap();
+        let (rest, n) = sum(buffer.tokens()).unwrap();
+        assert_eq!(n, 6);
+        assert!(rest.is_empty());
+        assert_eq!(rest.offset(), 11);
+
+        let buffer = TokenBuffer::lex("1 + 2 )").unwrap();
+        let (rest, n) = sum(buffer.tokens()).unwrap();
+        assert_eq!(n, 3);
+        assert_eq!(rest.offset(), 6);
+
+        let buffer = TokenBuffer::lex("+ 1").unwrap();
+        let Err(::nom::Err::Error(e)) = sum(buffer.tokens()) else {
+            panic!();
+        };
+        assert_eq!(e.input.offset(), 0);
+    }
+}
@@ -0,0 +1,179 @@
+#![cfg(feature = "dfa")]
+use lexi_matic::{Error, ErrorKind, Lexer, More};
+
+#[derive(Debug, PartialEq, Eq)]
+enum CommentError {
+    Unterminated,
+}
+
+impl std::fmt::Display for CommentError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("unterminated comment")
+    }
+}
+
+#[derive(Debug, Lexer, PartialEq, Eq)]
+#[lexer(error = CommentError, skip = r"[ \t\r\n\f]+")]
+enum Token<'a> {
+    #[token("let")]
+    Let,
+    #[regex("[a-zA-Z_][a-zA-Z0-9_]*")]
+    #[lexer(callback = keyword)]
+    Ident(&'a str),
+    #[regex(r"[0-9]+(\.[0-9]*)?")]
+    #[lexer(callback = number)]
+    Number(&'a str),
+    #[token("..")]
+    DotDot,
+    #[token("/*")]
+    #[lexer(callback = nested_comment)]
+    Comment(&'a str),
+    #[regex("#[a-z]*")]
+    #[lexer(callback = pragma)]
+    Pragma(&'a str),
+}
+
+// Contextual keyword.
+fn keyword<'a>(matched: &'a str, _: &str) -> Result<More<Token<'a>>, CommentError> {
+    Ok(match matched {
+        "fn" => More::Replace(Token::Let),
+        _ => More::Extend(0),
+    })
+}
+
+// `1..2` is a range, not the float `1.` followed by `.2`.
+fn number<'a>(matched: &str, remaining: &str) -> Result<More<Token<'a>>, CommentError> {
+    Ok(if matched.ends_with('.') && remaining.starts_with('.') {
+        More::Shrink(matched.len() - 1)
+    } else {
+        More::Extend(0)
+    })
+}
+
+fn nested_comment<'a>(_: &str, remaining: &str) -> Result<More<Token<'a>>, CommentError> {
+    let mut depth = 1;
+    let mut i = 0;
+    while i < remaining.len() {
+        if remaining[i..].starts_with("/*") {
+            depth += 1;
+            i += 2;
+        } else if remaining[i..].starts_with("*/") {
+            depth -= 1;
+            i += 2;
+            if depth == 0 {
+                return Ok(More::Extend(i));
+            }
+        } else {
+            i += 1;
+        }
+    }
+    Err(CommentError::Unterminated)
+}
+
+// Unknown pragmas are ignored.
+fn pragma<'a>(matched: &str, _: &str) -> Result<More<Token<'a>>, CommentError> {
+    Ok(if matched == "#inline" {
+        More::Extend(0)
+    } else {
+        More::Skip
+    })
+}
+
+#[test]
+fn test_callbacks() {
+    let input = "let fn x #foo 1..2 1.5 /* a /* b */ c */ #inline";
+    let expected = [
+        (0, Token::Let, 3),
+        (4, Token::Let, 6),
+        (7, Token::Ident("x"), 8),
+        (14, Token::Number("1"), 15),
+        (15, Token::DotDot, 17),
+        (17, Token::Number("2"), 18),
+        (19, Token::Number("1.5"), 22),
+        (23, Token::Comment("/* a /* b */ c */"), 40),
+        (41, Token::Pragma("#inline"), 48),
+    ];
+    itertools::assert_equal(Token::lex(input).map(Result::unwrap), expected);
+}
+
+#[test]
+fn test_user_error() {
+    let input = "x /* /* */";
+    let mut tokens = Token::lex(input);
+    assert_eq!(tokens.next(), Some(Ok((0, Token::Ident("x"), 1))));
+    let error = tokens.next().unwrap().unwrap_err();
+    assert_eq!(
+        error,
+        Error(
+            2,
+            ErrorKind::User {
+                end: 4,
+                error: CommentError::Unterminated
+            }
+        )
+    );
+    assert_eq!(error.to_string(), "unterminated comment at 2");
+
+    let error = Token::lex("x $").nth(1).unwrap().unwrap_err();
+    assert_eq!(error, Error(2, ErrorKind::NoMatch));
+    assert_eq!(error.to_string(), "lexical error at 2");
+}
+
+macro_rules! invalid {
+    ($name:ident, $($attr:tt)*) => {
+        #[derive(Debug, Lexer, PartialEq, Eq)]
+        #[lexer($($attr)* skip = " ", skip("!", more = past_end, label = "bang"))]
+        enum $name {
+            #[regex("[a-z]")]
+            #[lexer(more = extend)]
+            Letter,
+            #[regex("[0-9]+")]
+            #[lexer(callback = shrink)]
+            Digits,
+        }
+    };
+}
+
+invalid!(Invalid,);
+invalid!(InvalidNative, codegen = "native",);
+
+fn past_end(_: &str, remaining: &str) -> Option<usize> {
+    Some(remaining.len() + 1)
+}
+
+// `u` extends into the following char.
+fn extend(matched: &str, _: &str) -> Option<usize> {
+    Some(if matched == "u" { 1 } else { 0 })
+}
+
+fn shrink<T>(matched: &str, _: &str) -> Result<More<T>, std::convert::Infallible> {
+    Ok(match matched {
+        "0" => More::Shrink(0),
+        "99" => More::Shrink(3),
+        "7" => More::Extend(usize::MAX),
+        _ => More::Shrink(1),
+    })
+}
+
+#[test]
+fn test_invalid_length() {
+    fn first_error<'a, T: Lexer<'a>>(input: &'a str) -> Error<T::Error> {
+        T::lex(input).into_iter().find_map(Result::err).unwrap()
+    }
+    for (input, start, pattern, len) in [
+        ("a uéa", 2, "Letter", 2),
+        ("0", 0, "Digits", 0),
+        ("1 99", 2, "Digits", 3),
+        ("7", 0, "Digits", usize::MAX),
+        ("a !a", 2, "bang", 3),
+    ] {
+        let expected = Error(start, ErrorKind::Callback { pattern, len });
+        assert_eq!(first_error::<Invalid>(input), expected, "{input:?}");
+        assert_eq!(first_error::<InvalidNative>(input), expected, "{input:?}");
+    }
+    assert_eq!(Invalid::lex("12").next(), Some(Ok((0, Invalid::Digits, 1))));
+    assert_eq!(
+        first_error::<Invalid>("0").to_string(),
+        "callback of Digits returned invalid token length 0 at 0"
+    );
+}
@@ -0,0 +1,45 @@
+//! Helpers for tests that compare the code generation backends.
+#![allow(dead_code, unused_macros)]
+
+use std::fmt::Debug;
+
+use lexi_matic::{Error, Lexer};
+
+/// Derive the same lexer as `Plain` with the default DFA backend,
+/// `Accelerated` with acceleration and `Native` with acceleration and native
+/// code generation:
+///
+/// ```ignore
+/// backends! {
+///     #[lexer(skip = " +")]
+///     enum<'a> {
+///         #[regex("[a-z]+")]
+///         Ident(&'a str),
+///     }
+/// }
+/// ```
+macro_rules! backends {
+    (#[lexer($($attr:tt)*)] enum<$lt:lifetime> $variants:tt) => {
+        backends!(@derive Plain<$lt>, (), ($($attr)*), $variants);
+        backends!(@derive Accelerated<$lt>, (accelerate,), ($($attr)*), $variants);
+        backends!(@derive Native<$lt>, (accelerate, codegen = "native",), ($($attr)*), $variants);
+    };
+    (@derive $name:ident<$lt:lifetime>, ($($backend:tt)*), ($($attr:tt)*), {$($variants:tt)*}) => {
+        #[derive(Debug, lexi_matic::Lexer, PartialEq, Eq)]
+        #[lexer($($backend)* $($attr)*)]
+        enum $name<$lt> {
+            $($variants)*
+        }
+    };
+}
+
+/// The tokens and errors of `input` formatted with `Debug`, which doesn't
+/// include the name of the lexer, so that backends can be compared.
+pub fn lex<'a, T: Lexer<'a> + Debug>(input: &'a str) -> Vec<String>
+where
+    T::Iterator: Iterator<Item = Result<(usize, T, usize), Error<T::Error>>>,
+    T::Error: Debug,
+{
+    // Errors don't advance the iterator, so stop after a few of them.
+    T::lex(input).take(100).map(|t| format!("{t:?}")).collect()
+}
@@ -0,0 +1,19 @@
+#[cfg(feature = "dfa")]
+#[test]
+fn test_compile_fail() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
+
+/// Lexers with the default `dfa` backend need the `dfa` feature.
+#[cfg(not(feature = "dfa"))]
+#[test]
+fn test_compile_fail_without_dfa() {
+    trybuild::TestCases::new().compile_fail("tests/ui-no-dfa/*.rs");
+}
+
+/// Interning lexers can't be lexed in parallel.
+#[cfg(feature = "parallel")]
+#[test]
+fn test_compile_fail_parallel() {
+    trybuild::TestCases::new().compile_fail("tests/ui-parallel/*.rs");
+}
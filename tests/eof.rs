@@ -0,0 +1,88 @@
+#![cfg(feature = "dfa")]
+use lexi_matic::{Error, ErrorKind, Lexer};
+
+#[derive(Debug, Lexer, PartialEq, Eq)]
+#[lexer(skip = r"[ \t\r\n\f]+")]
+enum Token<'a> {
+    #[lexer(eof)]
+    Eof,
+    #[regex("[a-z]+")]
+    Ident(&'a str),
+    #[token(";")]
+    Semi,
+}
+
+#[derive(Debug, Lexer, PartialEq, Eq)]
+#[lexer(codegen = "native", skip = r"[ \t\r\n\f]+")]
+enum Native {
+    #[token(";")]
+    Semi,
+    #[lexer(eof)]
+    Eof,
+}
+
+#[test]
+fn test_eof() {
+    let expected = [
+        (0, Token::Ident("a"), 1),
+        (1, Token::Semi, 2),
+        (4, Token::Eof, 4),
+    ];
+    itertools::assert_equal(Token::lex("a;  ").map(Result::unwrap), expected);
+    itertools::assert_equal(Token::lex("").map(Result::unwrap), [(0, Token::Eof, 0)]);
+
+    let mut tokens = Native::lex(" ;");
+    assert_eq!(tokens.next(), Some(Ok((1, Native::Semi, 2))));
+    assert_eq!(tokens.next(), Some(Ok((2, Native::Eof, 2))));
+    assert_eq!(tokens.next(), None);
+    assert_eq!(tokens.next(), None);
+}
+
+#[test]
+fn test_consumed_after_eof() {
+    let mut tokens = Token::lex("a ");
+    tokens.next();
+    assert_eq!(tokens.next(), Some(Ok((2, Token::Eof, 2))));
+    assert_eq!(tokens.consumed, 2);
+    assert_eq!(&tokens.input[tokens.consumed..], "");
+    assert_eq!(tokens.next(), None);
+    assert_eq!(tokens.consumed, 2);
+
+    let mut tokens = Native::lex(";");
+    tokens.next();
+    assert_eq!(tokens.next(), Some(Ok((1, Native::Eof, 1))));
+    assert_eq!(tokens.consumed, 1);
+}
+
+#[test]
+fn test_eof_after_error() {
+    let mut tokens = Token::lex("a $");
+    assert_eq!(tokens.next(), Some(Ok((0, Token::Ident("a"), 1))));
+    assert_eq!(tokens.next(), Some(Err(Error(2, ErrorKind::NoMatch))));
+}
+
+#[cfg(feature = "std")]
+#[derive(Debug, Lexer, PartialEq, Eq)]
+#[lexer(owned, skip = " +")]
+enum Interned {
+    #[regex("[a-z]+")]
+    Ident(#[lexer(intern)] lexi_matic::Symbol),
+    #[lexer(eof)]
+    Eof,
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_eof_interned() {
+    use lexi_matic::{LexOwned, SimpleInterner};
+
+    let mut tokens = Interned::lex_with_interner("a ", SimpleInterner::new());
+    assert!(matches!(
+        tokens.next(),
+        Some(Ok((0, Interned::Ident(_), 1)))
+    ));
+    assert_eq!(tokens.next(), Some(Ok((2, Interned::Eof, 2))));
+    assert_eq!(tokens.consumed, 2);
+    let tokens: Vec<_> = Interned::lex_owned("a").map(Result::unwrap).collect();
+    assert_eq!(tokens.last(), Some(&(1, Interned::Eof, 1)));
+}
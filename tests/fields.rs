@@ -0,0 +1,74 @@
+#![cfg(feature = "dfa")]
+use std::ops::Range;
+
+use lexi_matic::Lexer;
+
+/// A span type converted from `Range<usize>`.
+#[derive(Debug, PartialEq, Eq)]
+struct Span(usize, usize);
+
+impl From<Range<usize>> for Span {
+    fn from(r: Range<usize>) -> Self {
+        Span(r.start, r.end)
+    }
+}
+
+macro_rules! token {
+    ($name:ident, $($attr:tt)*) => {
+        #[derive(Debug, Lexer, PartialEq, Eq)]
+        #[lexer($($attr)* skip = " +")]
+        enum $name<'a> {
+            #[regex("[a-z]+")]
+            Ident(#[lexer(text)] &'a str, #[lexer(span)] Span),
+            #[regex("[0-9]+")]
+            Int {
+                #[lexer(value = parse_int)]
+                value: u64,
+                #[lexer(start)]
+                start: usize,
+                #[lexer(span)]
+                span: Range<usize>,
+            },
+            #[regex("'[a-z]+")]
+            Label { name: &'a str },
+        }
+    };
+}
+
+token!(Dfa,);
+token!(Native, codegen = "native",);
+
+fn parse_int(s: &str) -> u64 {
+    s.parse().unwrap()
+}
+
+#[test]
+fn test_fields() {
+    let input = "ab 12 'x";
+    let tokens: Vec<_> = Dfa::lex(input).map(|t| t.unwrap().1).collect();
+    assert_eq!(
+        tokens,
+        [
+            Dfa::Ident("ab", Span(0, 2)),
+            Dfa::Int {
+                value: 12,
+                start: 3,
+                span: 3..5
+            },
+            Dfa::Label { name: "'x" },
+        ]
+    );
+    let tokens: Vec<_> = Native::lex(input).map(|t| t.unwrap().1).collect();
+    assert_eq!(
+        tokens,
+        [
+            Native::Ident("ab", Span(0, 2)),
+            Native::Int {
+                value: 12,
+                start: 3,
+                span: 3..5
+            },
+            Native::Label { name: "'x" },
+        ]
+    );
+}
@@ -0,0 +1,98 @@
+#![cfg(feature = "std")]
+use std::{fmt::Debug, str::FromStr};
+
+use lexi_matic::{Interner, LexOwned, Lexer, Lossless};
+
+#[derive(Debug, Lexer, PartialEq, Eq)]
+#[lexer(skip = " +")]
+enum Src<'src> {
+    #[regex("[a-z]+")]
+    Ident(&'src str),
+}
+
+/// Generic over the type of numbers.
+#[derive(Debug, Lexer, PartialEq, Eq)]
+#[lexer(skip = " +")]
+enum Num<'src, N>
+where
+    N: FromStr,
+    N::Err: Debug,
+{
+    #[regex("[0-9]+")]
+    Int(#[lexer(value = parse)] N),
+    #[regex("[a-z]+")]
+    Ident(&'src str),
+}
+
+fn parse<N: FromStr>(s: &str) -> N
+where
+    N::Err: Debug,
+{
+    s.parse().unwrap()
+}
+
+#[derive(Debug, Lexer, PartialEq, Eq)]
+#[lexer(skip = " +", codegen = "native", owned)]
+enum Prefix<const N: usize> {
+    #[regex("[a-z]+")]
+    Word(#[lexer(value = prefix::<N>)] [u8; N]),
+}
+
+fn prefix<const N: usize>(s: &str) -> [u8; N] {
+    let mut p = [b'.'; N];
+    let len = s.len().min(N);
+    p[..len].copy_from_slice(&s.as_bytes()[..len]);
+    p
+}
+
+/// Generic over the symbol type, with an interner type parameter named like
+/// the iterator's.
+#[derive(Debug, Lexer, PartialEq, Eq)]
+#[lexer(skip = " +")]
+enum Interned<I: Copy> {
+    #[regex("[a-z]+")]
+    Ident(#[lexer(intern)] I),
+}
+
+/// Interns strings as their length.
+struct Len;
+
+impl Interner for Len {
+    type Symbol = usize;
+    fn intern(&mut self, s: &str) -> usize {
+        s.len()
+    }
+}
+
+#[test]
+fn test_lifetime() {
+    let tokens: Vec<_> = Src::lex("ab c").map(|t| t.unwrap().1).collect();
+    assert_eq!(tokens, [Src::Ident("ab"), Src::Ident("c")]);
+}
+
+#[test]
+fn test_type_param() {
+    let tokens: Vec<_> = Num::<u8>::lex("12 x").map(|t| t.unwrap().1).collect();
+    assert_eq!(tokens, [Num::Int(12u8), Num::Ident("x")]);
+    let tokens: Vec<_> = Num::<f64>::lex_lossless("1 ").map(|(_, p, _)| p).collect();
+    assert_eq!(tokens.len(), 2);
+    assert_eq!(Num::<u8>::SKIP_LABELS, [" +"]);
+}
+
+#[test]
+fn test_const_param() {
+    let tokens: Vec<_> = Prefix::<2>::lex("abc d").map(|t| t.unwrap().1).collect();
+    assert_eq!(tokens, [Prefix::Word(*b"ab"), Prefix::Word(*b"d.")]);
+    assert_eq!(Prefix::<3>::lex_owned("abcd").count(), 1);
+}
+
+#[test]
+fn test_symbol_param() {
+    let tokens: Vec<_> = Interned::lex_with_interner("abc d", Len)
+        .map(|t| t.unwrap().1)
+        .collect();
+    assert_eq!(tokens, [Interned::Ident(3), Interned::Ident(1)]);
+    // With the default interner.
+    let tokens: Vec<_> = Interned::lex("abc abc").map(|t| t.unwrap().1).collect();
+    assert_eq!(tokens[0], tokens[1]);
+}
@@ -1,4 +1,5 @@
 // Experiment with indentation sensitive lexing like in python.
+#![cfg(feature = "dfa")]
 
 use std::{cmp::Ordering, collections::VecDeque, fmt, iter::Peekable};
 
@@ -0,0 +1,92 @@
+#![cfg(feature = "alloc")]
+use lexi_matic::{inspect::Inspect, Lexer};
+
+#[derive(Debug, Lexer)]
+#[lexer(skip(r"[ \n]+", label = "space"))]
+enum Token {
+    #[token("ab")]
+    Ab,
+    #[regex("a+")]
+    A,
+    #[lexer(eof)]
+    Eof,
+}
+
+#[test]
+fn test_states() {
+    let dfa = Token::dfa();
+    assert_eq!(dfa.patterns(), ["Ab", "A", "space"]);
+    assert!(dfa.memory_usage() > 0);
+
+    let states = dfa.states();
+    assert_eq!(states.len(), dfa.state_count());
+    let a = states[0].next(b'a').unwrap();
+    assert_eq!(states[a].matches, ["A"]);
+    let ab = states[a].next(b'b').unwrap();
+    assert_eq!(states[ab].matches, ["Ab"]);
+    assert_eq!(states[ab].next(b'b'), None);
+    let space = states[0].next(b' ').unwrap();
+    let spaces = states[space].next(b'\n').unwrap();
+    assert_eq!(states[spaces].matches, ["space"]);
+    assert_eq!(states[spaces].next(b' '), Some(spaces));
+}
+
+#[test]
+fn test_to_dot() {
+    let dot = Token::dfa().to_dot();
+    let expected = r#"  rankdir=LR;
+  node [shape=circle];
+  0 [label="0"];
+  1 [label="1\nspace", shape=doublecircle];
+  2 [label="2\nA", shape=doublecircle];
+  3 [label="3\nspace", shape=doublecircle];
+  4 [label="4\nA", shape=doublecircle];
+  5 [label="5\nAb", shape=doublecircle];
+  0 -> 1 [label="\\x0A, \\x20"];
+  0 -> 2 [label="a"];
+  1 -> 3 [label="\\x0A, \\x20"];
+  2 -> 4 [label="a"];
+  2 -> 5 [label="b"];
+  3 -> 3 [label="\\x0A, \\x20"];
+  4 -> 4 [label="a"];
+}
+"#;
+    assert!(dot.starts_with("digraph dfa {\n  // 6 states, "), "{dot}");
+    assert!(dot.ends_with(expected), "{dot}");
+}
+
+#[derive(Debug, Lexer)]
+#[lexer(accelerate)]
+enum Accelerated {
+    #[regex("//[^\n]*")]
+    Comment,
+}
+
+#[test]
+fn test_accelerated() {
+    let states = Accelerated::dfa().states();
+    assert!(states.iter().any(|s| s.accelerated));
+    assert!(Accelerated::dfa().to_dot().contains("style=bold"));
+}
+
+#[derive(Debug, Lexer)]
+enum OwnItems {
+    #[token("a")]
+    A,
+}
+
+// Generated items don't clash with the lexer's own.
+impl OwnItems {
+    const SKIP_LABELS: &'static [&'static str] = &["own"];
+
+    fn dfa() -> &'static str {
+        "own"
+    }
+}
+
+#[test]
+fn test_own_items() {
+    assert_eq!(OwnItems::SKIP_LABELS, ["own"]);
+    assert_eq!(OwnItems::dfa(), "own");
+    assert_eq!(<OwnItems as Inspect>::dfa().patterns(), ["A"]);
+}
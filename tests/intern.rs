@@ -0,0 +1,82 @@
+#![cfg(feature = "std")]
+
+use std::collections::HashMap;
+
+use lexi_matic::{Interner, Lexer, SimpleInterner, Symbol};
+
+#[derive(Debug, Lexer, PartialEq, Eq)]
+#[lexer(skip = r"[ \t\r\n\f]+")]
+enum Token<'a> {
+    #[token("let")]
+    Let,
+    #[regex("[a-zA-Z_][a-zA-Z0-9_]*")]
+    Ident(#[lexer(intern)] Symbol),
+    #[regex("[0-9]+")]
+    Int(&'a str),
+}
+
+#[test]
+fn test_intern() {
+    let mut interner = SimpleInterner::new();
+    let tokens: Vec<_> = Token::lex_with_interner("let foo bar 1 foo", &mut interner)
+        .map(Result::unwrap)
+        .collect();
+    let (foo, bar) = (interner.get("foo").unwrap(), interner.get("bar").unwrap());
+    assert_eq!(
+        tokens,
+        [
+            (0, Token::Let, 3),
+            (4, Token::Ident(foo), 7),
+            (8, Token::Ident(bar), 11),
+            (12, Token::Int("1"), 13),
+            (14, Token::Ident(foo), 17),
+        ]
+    );
+    assert_eq!(interner.len(), 2);
+    assert_eq!(interner.resolve(foo), "foo");
+    assert_eq!(interner.resolve(bar), "bar");
+}
+
+#[test]
+fn test_default_interner() {
+    let mut tokens = Token::lex("foo foo");
+    assert_eq!(tokens.by_ref().count(), 2);
+    assert_eq!(tokens.interner.len(), 1);
+}
+
+/// A user supplied interner.
+#[derive(Default)]
+struct Counter(HashMap<String, usize>);
+
+impl Interner for Counter {
+    type Symbol = usize;
+    fn intern(&mut self, s: &str) -> usize {
+        let n = self.0.len();
+        *self.0.entry(s.into()).or_insert(n)
+    }
+}
+
+#[derive(Debug, Lexer, PartialEq, Eq)]
+#[lexer(owned, interner = Counter, skip = r"[ \t\r\n\f]+")]
+enum OwnedToken {
+    #[regex("[a-z]+")]
+    Ident(#[lexer(intern)] usize),
+}
+
+#[test]
+fn test_custom_interner() {
+    use lexi_matic::LexOwned;
+
+    let tokens: Vec<_> = OwnedToken::lex_owned("a b a c")
+        .map(|t| t.unwrap().1)
+        .collect();
+    assert_eq!(
+        tokens,
+        [
+            OwnedToken::Ident(0),
+            OwnedToken::Ident(1),
+            OwnedToken::Ident(0),
+            OwnedToken::Ident(2),
+        ]
+    );
+}
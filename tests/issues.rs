@@ -1,4 +1,5 @@
 // Test that we don't have these logos issues.
+#![cfg(feature = "dfa")]
 
 use lexi_matic::Lexer;
 
@@ -0,0 +1,45 @@
+//! An example of a LALRPOP parser using a lexi-matic lexer.
+use lalrpop_util::lalrpop_mod;
+use lexi_matic::Lexer;
+
+lalrpop_mod!(pub calc);
+
+#[derive(Debug, Clone, PartialEq, Eq, Lexer)]
+#[lexer(skip = r"[ \t\r\n\f]+", skip = "//[^\n]*")]
+pub enum Token<'a> {
+    #[token("let")]
+    Let,
+    #[token("=")]
+    Eq,
+    #[token(";")]
+    Semi,
+    #[token("+")]
+    Plus,
+    #[token("-")]
+    Minus,
+    #[token("*")]
+    Star,
+    #[token("(")]
+    LParen,
+    #[token(")")]
+    RParen,
+    #[regex("[a-zA-Z_][a-zA-Z0-9_]*")]
+    Ident(&'a str),
+    #[regex("[0-9]+")]
+    Num(#[lexer(value = parse_num)] i64),
+    #[lexer(eof)]
+    Eof,
+}
+
+fn parse_num(s: &str) -> i64 {
+    s.parse().unwrap()
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Expr<'a> {
+    Num(i64),
+    Var(&'a str),
+    Add(Box<Expr<'a>>, Box<Expr<'a>>),
+    Sub(Box<Expr<'a>>, Box<Expr<'a>>),
+    Mul(Box<Expr<'a>>, Box<Expr<'a>>),
+}
@@ -0,0 +1,65 @@
+use lalrpop_util::ParseError;
+use lexi_matic::{
+    lalrpop::{error_span, tokens},
+    Error, ErrorKind,
+};
+use lexi_matic_lalrpop_test::{
+    calc::{ExprParser, ProgramParser},
+    Expr, Token,
+};
+
+#[test]
+fn test_parse() {
+    let input = "let x = 1 + 2 * y; // comment\nlet y = (x - 3);";
+    let program = ProgramParser::new().parse(tokens::<Token>(input)).unwrap();
+    assert_eq!(
+        program,
+        [
+            (
+                "x",
+                Expr::Add(
+                    Box::new(Expr::Num(1)),
+                    Box::new(Expr::Mul(Box::new(Expr::Num(2)), Box::new(Expr::Var("y"))))
+                )
+            ),
+            (
+                "y",
+                Expr::Sub(Box::new(Expr::Var("x")), Box::new(Expr::Num(3)))
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_lexical_error() {
+    let input = "1 + $ + 2";
+    let error = ExprParser::new().parse(tokens::<Token>(input)).unwrap_err();
+    assert_eq!(
+        error,
+        ParseError::User {
+            error: Error(4, ErrorKind::NoMatch)
+        }
+    );
+    assert_eq!(error_span(&error), 4..4);
+    if let ParseError::User { error } = error {
+        assert_eq!(error.to_string(), "lexical error at 4");
+    }
+}
+
+#[test]
+fn test_parse_errors() {
+    let error = ExprParser::new()
+        .parse(tokens::<Token>("1 + * 2"))
+        .unwrap_err();
+    assert_eq!(error_span(&error), 4..5);
+    assert!(matches!(error, ParseError::UnrecognizedToken { .. }));
+
+    let error = ExprParser::new()
+        .parse(tokens::<Token>("(1 + 2"))
+        .unwrap_err();
+    assert_eq!(error_span(&error), 6..6);
+    assert!(matches!(error, ParseError::UnrecognizedEof { .. }));
+
+    let error = ExprParser::new().parse(tokens::<Token>("1 2")).unwrap_err();
+    assert_eq!(error_span(&error), 2..3);
+}
@@ -1,6 +1,7 @@
 // Experiment with layout rules like in Haskell.
 //
 // But not exactly like in Haskell, because we will close layouts when seeing `in` or `)` instead of using `parse-error`.
+#![cfg(feature = "dfa")]
 
 use std::{collections::VecDeque, iter::Peekable};
 
@@ -0,0 +1,156 @@
+#![cfg(feature = "alloc")]
+
+use std::borrow::Cow;
+
+use lexi_matic::{
+    literals::{self, EscapeError, EscapeErrorKind, Escapes, LineContinuation},
+    Lexer,
+};
+
+fn error(offset: usize, len: usize, kind: EscapeErrorKind) -> EscapeError {
+    EscapeError { offset, len, kind }
+}
+
+#[test]
+fn test_borrowed_without_escapes() {
+    assert!(matches!(
+        Escapes::RUST.unescape("no escapes ✓"),
+        Ok(Cow::Borrowed("no escapes ✓"))
+    ));
+    assert!(matches!(
+        literals::json_string(r#""x""#),
+        Ok(Cow::Borrowed("x"))
+    ));
+}
+
+#[test]
+fn test_c() {
+    let c = |s| Escapes::C.unescape(s);
+    assert_eq!(c(r#"a\tb\n\\\'\"\?\a\v"#).unwrap(), "a\tb\n\\'\"?\x07\x0b");
+    assert_eq!(c(r"\101\0\7\1234").unwrap(), "A\0\x07S4");
+    assert_eq!(c(r"\x41\x7e\xe9").unwrap(), "A~é");
+    assert_eq!(c(r"\u00e9\U0001F600").unwrap(), "é😀");
+    assert_eq!(c("a\\\nb").unwrap(), "ab");
+    assert_eq!(c(r"ab\q"), Err(error(2, 2, EscapeErrorKind::UnknownEscape)));
+    assert_eq!(
+        c(r"\xfffffffff"),
+        Err(error(0, 11, EscapeErrorKind::OutOfRange))
+    );
+    assert_eq!(c(r"\x"), Err(error(0, 2, EscapeErrorKind::InvalidHex)));
+    assert_eq!(
+        c(r"x\"),
+        Err(error(1, 1, EscapeErrorKind::TrailingBackslash))
+    );
+}
+
+#[test]
+fn test_json() {
+    let json = |s| Escapes::JSON.unescape(s);
+    assert_eq!(json(r#"\"\\\/\b\f\n\r\t"#).unwrap(), "\"\\/\x08\x0c\n\r\t");
+    assert_eq!(json(r"\u00e9 \ud83d\ude00").unwrap(), "é 😀");
+    assert_eq!(
+        json(r"ab\ud83d"),
+        Err(error(2, 6, EscapeErrorKind::OutOfRange))
+    );
+    assert_eq!(
+        json(r"\u12"),
+        Err(error(0, 4, EscapeErrorKind::InvalidUnicode))
+    );
+    assert_eq!(
+        json(r"\x41"),
+        Err(error(0, 2, EscapeErrorKind::UnknownEscape))
+    );
+    assert_eq!(
+        json("\\\n"),
+        Err(error(0, 2, EscapeErrorKind::UnknownEscape))
+    );
+}
+
+#[test]
+fn test_rust() {
+    let rust = |s| Escapes::RUST.unescape(s);
+    assert_eq!(rust(r"\n\r\t\\\0\'\x7F").unwrap(), "\n\r\t\\\0'\x7f");
+    assert_eq!(rust(r"\u{e9}\u{1F600}").unwrap(), "é😀");
+    assert_eq!(rust("a\\\n    \n  b").unwrap(), "ab");
+    assert_eq!(rust(r"\x80"), Err(error(0, 4, EscapeErrorKind::OutOfRange)));
+    assert_eq!(
+        rust(r"\u{d800}"),
+        Err(error(0, 8, EscapeErrorKind::OutOfRange))
+    );
+    assert_eq!(
+        rust(r"\u{1234567}"),
+        Err(error(0, 9, EscapeErrorKind::InvalidUnicode))
+    );
+    assert_eq!(
+        rust(r"\A"),
+        Err(error(0, 2, EscapeErrorKind::UnknownEscape))
+    );
+}
+
+#[test]
+fn test_python() {
+    let python = |s| Escapes::PYTHON.unescape(s);
+    assert_eq!(python(r"\x41\xe9\101\u00e9\U0001F600").unwrap(), "AéAé😀");
+    assert_eq!(python(r"\q\d").unwrap(), r"\q\d");
+    assert_eq!(python("a\\\nb").unwrap(), "ab");
+    assert_eq!(
+        python(r"\x4"),
+        Err(error(0, 3, EscapeErrorKind::InvalidHex))
+    );
+}
+
+#[test]
+fn test_custom() {
+    let escapes = Escapes {
+        keep_unknown: true,
+        line_continuation: LineContinuation::None,
+        ..Escapes::JSON
+    };
+    assert_eq!(escapes.unescape("\\q\\\n").unwrap(), "\\q\\\n");
+}
+
+#[test]
+fn test_unquote_offsets() {
+    assert_eq!(
+        literals::rust_string(r#""ab\q""#),
+        Err(error(3, 2, EscapeErrorKind::UnknownEscape))
+    );
+    assert_eq!(literals::c_string(r"'\n'").unwrap(), "\n");
+    assert_eq!(literals::python_string("'é'").unwrap(), "é");
+    assert_eq!(
+        literals::json_string("\""),
+        Err(error(0, 1, EscapeErrorKind::Unquoted))
+    );
+}
+
+#[derive(Debug, Lexer, PartialEq, Eq)]
+#[lexer(skip = r"[ \t\r\n\f]+")]
+enum Token<'a> {
+    #[regex(r#""([^"\\]|\\(.|\n))*""#)]
+    Str(#[lexer(value = literals::json_string)] Result<Cow<'a, str>, EscapeError>),
+    #[regex("[0-9]+")]
+    Int(#[lexer(value = parse_int)] u64),
+}
+
+fn parse_int(s: &str) -> u64 {
+    s.parse().unwrap()
+}
+
+#[test]
+fn test_value_callback() {
+    let tokens: Vec<_> = Token::lex(r#""a\tb" 42 "\q""#)
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(
+        tokens,
+        [
+            (0, Token::Str(Ok("a\tb".into())), 6),
+            (7, Token::Int(42), 9),
+            (
+                10,
+                Token::Str(Err(error(1, 2, EscapeErrorKind::UnknownEscape))),
+                14
+            ),
+        ]
+    );
+}
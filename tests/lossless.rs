@@ -0,0 +1,163 @@
+#![cfg(feature = "dfa")]
+use lexi_matic::{ErrorKind, Lexer, Lossless, More, Piece};
+
+#[macro_use]
+mod common;
+
+backends! {
+    #[lexer(
+        error = &'static str,
+        skip = r"[ \t\n]+",
+        skip(r"#[a-z]*", callback = pragma, label = "pragma"),
+    )]
+    enum<'a> {
+        #[regex("[a-z]+")]
+        #[lexer(callback = ident)]
+        Ident(&'a str),
+        #[token("*")]
+        Star,
+        #[lexer(eof)]
+        Eof,
+    }
+}
+
+// `#` alone is skipped by the callback.
+fn pragma<T>(matched: &str, _: &str) -> Result<More<T>, &'static str> {
+    if matched == "#" {
+        Ok(More::Skip)
+    } else {
+        Ok(More::Extend(0))
+    }
+}
+
+fn ident<T>(matched: &str, _: &str) -> Result<More<T>, &'static str> {
+    match matched {
+        "goto" => Err("goto"),
+        "skip" => Ok(More::Skip),
+        _ => Ok(More::Extend(0)),
+    }
+}
+
+fn pieces<'a, T: Lossless<'a, Error = &'static str>>(
+    input: &'a str,
+) -> Vec<(usize, String, usize)> {
+    T::lex_lossless(input)
+        .map(|(start, piece, end)| {
+            let piece = match piece {
+                Piece::Token(t) => T::VARIANTS[t.variant_index()].to_string(),
+                Piece::Skip(Some(k)) => T::SKIP_LABELS[k].to_string(),
+                Piece::Skip(None) => "skip".to_string(),
+                Piece::Error(e) => format!("{e:?}"),
+            };
+            (start, piece, end)
+        })
+        .collect()
+}
+
+#[test]
+fn test_lossless() {
+    let input = "a *? #x\n#é goto skip";
+    let expected = [
+        (0, "Ident", 1),
+        (1, r"[ \t\n]+", 2),
+        (2, "Star", 3),
+        (3, "NoMatch", 4),
+        (4, r"[ \t\n]+", 5),
+        (5, "pragma", 7),
+        (7, r"[ \t\n]+", 8),
+        // Skipped by the pattern's callback.
+        (8, "pragma", 9),
+        (9, "NoMatch", 11),
+        (11, r"[ \t\n]+", 12),
+        (12, "User { end: 16, error: \"goto\" }", 16),
+        (16, r"[ \t\n]+", 17),
+        (17, "skip", 21),
+        (21, "Eof", 21),
+    ];
+    let expected: Vec<_> = expected
+        .into_iter()
+        .map(|(s, p, e)| (s, p.to_string(), e))
+        .collect();
+    assert_eq!(pieces::<Plain>(input), expected);
+    assert_eq!(pieces::<Accelerated>(input), expected);
+    assert_eq!(pieces::<Native>(input), expected);
+}
+
+#[test]
+fn test_lossless_covers_input() {
+    for input in ["", "é", "a  b\t\t*#", "?? goto"] {
+        let mut end = 0;
+        for (start, _, e) in Plain::lex_lossless(input) {
+            assert_eq!(start, end, "{input:?}");
+            end = e;
+        }
+        assert_eq!(end, input.len(), "{input:?}");
+    }
+}
+
+#[test]
+fn test_lossless_tokens() {
+    // The same tokens as `lex`.
+    let input = "a * b";
+    let tokens: Vec<_> = Plain::lex_lossless(input)
+        .filter_map(|(s, p, e)| match p {
+            Piece::Token(t) => Some((s, t, e)),
+            _ => None,
+        })
+        .collect();
+    let expected: Vec<_> = Plain::lex(input).map(Result::unwrap).collect();
+    assert_eq!(tokens, expected);
+    assert!(matches!(
+        Plain::lex_lossless("goto").next(),
+        Some((0, Piece::Error(ErrorKind::User { end: 4, .. }), 4))
+    ));
+}
+
+#[test]
+fn test_regexes() {
+    assert_eq!(
+        Plain::REGEXES,
+        ["[a-z]+", r"\*", "[\t\n ]+", r"(?:\#[a-z]*)"]
+    );
+    assert_eq!(Native::REGEXES, Plain::REGEXES);
+}
+
+#[cfg(feature = "rowan")]
+mod with_rowan {
+    use lexi_matic::rowan::{kind_count, kind_name, tokens};
+    use rowan::{GreenNodeBuilder, SyntaxKind};
+
+    use super::Plain;
+
+    #[test]
+    fn test_rowan() {
+        let input = "a *?#x goto";
+        let kinds: Vec<_> = tokens::<Plain>(input)
+            .map(|(k, text)| (kind_name::<Plain>(k).unwrap(), text))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                ("Ident", "a"),
+                (r"[ \t\n]+", " "),
+                ("Star", "*"),
+                ("error", "?"),
+                ("pragma", "#x"),
+                (r"[ \t\n]+", " "),
+                ("error", "goto"),
+            ]
+        );
+
+        assert_eq!(kind_count::<Plain>(), SyntaxKind(7));
+        assert_eq!(kind_name::<Plain>(SyntaxKind(5)), Some("skip"));
+        assert_eq!(kind_name::<Plain>(kind_count::<Plain>()), None);
+
+        let mut builder = GreenNodeBuilder::new();
+        builder.start_node(kind_count::<Plain>());
+        for (kind, text) in tokens::<Plain>(input) {
+            builder.token(kind, text);
+        }
+        builder.finish_node();
+        assert_eq!(builder.finish().to_string(), input);
+    }
+}
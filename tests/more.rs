@@ -0,0 +1,118 @@
+#![cfg(feature = "dfa")]
+use lexi_matic::{more, Lexer};
+
+#[derive(Debug, Lexer, PartialEq, Eq)]
+#[lexer(skip = r"[ \t\r\n\f]+")]
+enum Token<'a> {
+    #[regex("[a-zA-Z_][a-zA-Z0-9_]*")]
+    Ident(&'a str),
+    #[regex(r##"b?r#*""##)]
+    #[lexer(more = more::rust_raw_string)]
+    RustRaw(&'a str),
+    #[regex(r"(--)?\[=*\[")]
+    #[lexer(more = more::lua_long_bracket)]
+    Lua(&'a str),
+    #[regex(r#"(u8|u|U|L)?R"[^ ()\\\t\n]*\("#)]
+    #[lexer(more = more::cpp_raw_string)]
+    CppRaw(&'a str),
+    #[regex(r#"<<[-~]?('[A-Z]+'|"[A-Z]+"|[A-Z]+)"#)]
+    #[lexer(more = more::heredoc)]
+    Heredoc(&'a str),
+}
+
+fn lex(input: &str) -> Vec<Result<(usize, Token<'_>, usize), usize>> {
+    // Errors don't advance the iterator, so stop at the first one.
+    let mut tokens = Vec::new();
+    for t in Token::lex(input) {
+        match t {
+            Ok(t) => tokens.push(Ok(t)),
+            Err(e) => {
+                tokens.push(Err(e.0));
+                break;
+            }
+        }
+    }
+    tokens
+}
+
+#[test]
+fn test_rust_raw_string() {
+    assert_eq!(more::rust_raw_string("r\"", "abc\" x"), Some(4));
+    assert_eq!(more::rust_raw_string("r#\"", "a\"b\"# x"), Some(5));
+    // `"#` doesn't close `r##"`, but `"###` closes it, leaving one `#`.
+    assert_eq!(more::rust_raw_string("r##\"", "\"# \"###"), Some(6));
+    assert_eq!(more::rust_raw_string("br##\"", "☃\"##"), Some(6));
+    assert_eq!(more::rust_raw_string("r##\"", "abc\"#"), None);
+    assert_eq!(more::rust_raw_string("r\"", ""), None);
+
+    let input = r###"r##"a "# b"## x"###;
+    assert_eq!(
+        lex(input),
+        [
+            Ok((0, Token::RustRaw(r###"r##"a "# b"##"###), 13)),
+            Ok((14, Token::Ident("x"), 15)),
+        ]
+    );
+}
+
+#[test]
+fn test_lua_long_bracket() {
+    assert_eq!(more::lua_long_bracket("[[", "a]]"), Some(3));
+    assert_eq!(
+        more::lua_long_bracket("[==[", "]] ]=] ]===] ]==]"),
+        Some(17)
+    );
+    assert_eq!(more::lua_long_bracket("--[=[", "[[ ]] ]=]"), Some(9));
+    assert_eq!(more::lua_long_bracket("[==[", "]=]"), None);
+    assert_eq!(more::lua_long_bracket("[=[", "]="), None);
+
+    let input = "[=[ ]] ]=] x [[ ]=]";
+    assert_eq!(
+        lex(input),
+        [
+            Ok((0, Token::Lua("[=[ ]] ]=]"), 10)),
+            Ok((11, Token::Ident("x"), 12)),
+            Err(13),
+        ]
+    );
+}
+
+#[test]
+fn test_cpp_raw_string() {
+    assert_eq!(more::cpp_raw_string("R\"(", "a)\""), Some(3));
+    assert_eq!(more::cpp_raw_string("R\"xy(", ")\" )x\" )xy\""), Some(11));
+    assert_eq!(more::cpp_raw_string("u8R\"-(", "☃)-\""), Some(6));
+    assert_eq!(more::cpp_raw_string("R\"xy(", ")xy"), None);
+    assert_eq!(more::cpp_raw_string("R\"(", ")x\""), None);
+
+    let input = "R\"a()\")a\" x";
+    assert_eq!(
+        lex(input),
+        [
+            Ok((0, Token::CppRaw("R\"a()\")a\""), 9)),
+            Ok((10, Token::Ident("x"), 11)),
+        ]
+    );
+}
+
+#[test]
+fn test_heredoc() {
+    assert_eq!(more::heredoc("<<EOF", "\nbody\nEOF\n"), Some(9));
+    assert_eq!(more::heredoc("<<EOF", " | cat\nEOF"), Some(10));
+    // Neither an indented nor a longer delimiter closes it.
+    assert_eq!(more::heredoc("<<EOF", "\n  EOF\nEOFX\nEOF"), Some(15));
+    assert_eq!(more::heredoc("<<EOF", "\n  EOF\nEOFX\n"), None);
+    assert_eq!(more::heredoc("<<-EOF", "\n\tx\n\tEOF\r\n"), Some(8));
+    assert_eq!(more::heredoc("<<'EOF'", "\n$x\nEOF"), Some(7));
+    assert_eq!(more::heredoc("<<EOF", ""), None);
+    assert_eq!(more::heredoc("<<EOF", "\nEO"), None);
+
+    let input = "<<\"END\"\nEND x\nEND\nx";
+    assert_eq!(
+        lex(input),
+        [
+            Ok((0, Token::Heredoc("<<\"END\"\nEND x\nEND"), 17)),
+            Ok((18, Token::Ident("x"), 19)),
+        ]
+    );
+}
@@ -0,0 +1,97 @@
+use lexi_matic::Lexer;
+
+mod common;
+
+#[derive(Debug, Lexer, PartialEq, Eq)]
+#[lexer(codegen = "native", skip = "//[^\n]*", skip = r"[ \t\r\n\f]+")]
+enum Token<'a> {
+    #[token("import")]
+    Import,
+    #[token(";")]
+    Semi,
+    #[regex("[a-zA-Z_][a-zA-Z0-9_]*")]
+    Ident(&'a str),
+    #[regex("[0-9]+")]
+    Int(&'a str),
+    #[regex(r##"r#*""##)]
+    #[lexer(more = end_raw_str)]
+    RawStr(&'a str),
+    #[regex(r"\p{Greek}+")]
+    Greek(&'a str),
+}
+
+fn end_raw_str(matched: &str, remaining: &str) -> Option<usize> {
+    let start: String = matched[1..].chars().rev().collect();
+    remaining.find(&start).map(|l| l + start.len())
+}
+
+#[cfg(feature = "dfa")]
+#[derive(Debug, Lexer, PartialEq, Eq)]
+#[lexer(skip = "//[^\n]*", skip = r"[ \t\r\n\f]+")]
+enum DfaToken<'a> {
+    #[token("import")]
+    Import,
+    #[token(";")]
+    Semi,
+    #[regex("[a-zA-Z_][a-zA-Z0-9_]*")]
+    Ident(&'a str),
+    #[regex("[0-9]+")]
+    Int(&'a str),
+    #[regex(r##"r#*""##)]
+    #[lexer(more = end_raw_str)]
+    RawStr(&'a str),
+    #[regex(r"\p{Greek}+")]
+    Greek(&'a str),
+}
+
+#[test]
+fn test_tokens() {
+    let input = Token::lex(
+        r####"import // ...
+import1; r#"abc"#
+αβγ 42"####,
+    );
+    let expected = [
+        (0, Token::Import, 6),
+        (14, Token::Ident("import1"), 21),
+        (21, Token::Semi, 22),
+        (23, Token::RawStr(r###"r#"abc"#"###), 31),
+        (32, Token::Greek("αβγ"), 38),
+        (39, Token::Int("42"), 41),
+    ];
+    itertools::assert_equal(input.map(Result::unwrap), expected);
+}
+
+#[cfg(feature = "dfa")]
+#[test]
+fn test_same_as_dfa() {
+    let inputs = [
+        "import;import1 importimport",
+        "r##\"a\"#\"## ; // x\n 123abc",
+        "αβγ δ; x // ...",
+        "import ? x",
+        "r#\"unterminated",
+        "",
+    ];
+    for input in inputs {
+        assert_eq!(
+            common::lex::<Token>(input),
+            common::lex::<DfaToken>(input),
+            "{input:?}"
+        );
+    }
+}
+
+#[test]
+fn test_no_match() {
+    #[derive(Lexer, Debug, PartialEq, Eq)]
+    #[lexer(codegen = "native")]
+    enum Foo {
+        #[token("FOOB")]
+        Foo,
+    }
+
+    let mut lex = Foo::lex("FOO");
+    assert_eq!(lex.next().unwrap().unwrap_err().0, 0);
+    assert!(Foo::lex("FOOB").map(|r| r.unwrap().1).eq([Foo::Foo]));
+}
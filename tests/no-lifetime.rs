@@ -1,3 +1,4 @@
+#![cfg(feature = "dfa")]
 use lexi_matic::Lexer;
 
 #[derive(Debug, Lexer, PartialEq, Eq)]
@@ -0,0 +1,36 @@
+#![no_std]
+#![forbid(unsafe_code)]
+
+use lexi_matic::{LexOwned, Lexer, More};
+
+#[derive(Debug, Lexer)]
+#[lexer(accelerate, skip = r"[ \t\r\n\f]+", skip = "//[^\n]*")]
+pub enum Token<'a> {
+    #[token("let")]
+    Let,
+    #[regex("[a-zA-Z_][a-zA-Z0-9_]*")]
+    #[lexer(callback = ident)]
+    Ident(&'a str),
+    #[regex(r##"r#*""##)]
+    #[lexer(more = lexi_matic::more::rust_raw_string)]
+    RawStr(&'a str),
+}
+
+fn ident<'a>(matched: &str, _: &str) -> Result<More<Token<'a>>, core::convert::Infallible> {
+    Ok(match matched {
+        "fn" => More::Replace(Token::Let),
+        _ => More::Extend(0),
+    })
+}
+
+#[derive(Debug, Lexer)]
+#[lexer(codegen = "native", owned, skip = " +")]
+pub enum Native {
+    #[regex("[0-9]+")]
+    Int,
+}
+
+pub fn count(input: &str) -> usize {
+    Token::lex(input).take_while(Result::is_ok).count()
+        + Native::lex_owned(input).take_while(Result::is_ok).count()
+}
@@ -0,0 +1,99 @@
+#![cfg(feature = "alloc")]
+use lexi_matic::{
+    literals::number::{self, Number, NumberError, NumberErrorKind, NumberFormat},
+    Lexer,
+};
+
+#[derive(Debug, Lexer, PartialEq)]
+#[lexer(skip = r"[ \t\r\n\f]+")]
+enum Token<'a> {
+    #[regex(r"[0-9][0-9a-zA-Z_]*")]
+    Int(#[lexer(value = number::rust_number)] Result<Number<'a>, NumberError>),
+    #[regex(r"[0-9][0-9_]*(\.[0-9][0-9_]*)?([eE][+-]?[0-9_]+)?(f32|f64)?")]
+    Float(#[lexer(value = number::rust_number)] Result<Number<'a>, NumberError>),
+}
+
+fn int(value: u128, radix: u32, suffix: &str) -> Result<Number<'_>, NumberError> {
+    Ok(Number::Int {
+        value,
+        radix,
+        suffix,
+    })
+}
+
+fn float(value: f64, suffix: &str) -> Result<Number<'_>, NumberError> {
+    Ok(Number::Float { value, suffix })
+}
+
+fn error(offset: usize, len: usize, kind: NumberErrorKind) -> Result<Number<'static>, NumberError> {
+    Err(NumberError { offset, len, kind })
+}
+
+#[test]
+fn test_rust_integers() {
+    assert_eq!(number::rust_number("0"), int(0, 10, ""));
+    assert_eq!(number::rust_number("1_000_000"), int(1_000_000, 10, ""));
+    assert_eq!(number::rust_number("0xff_FFu32"), int(0xffff, 16, "u32"));
+    assert_eq!(number::rust_number("0o777"), int(0o777, 8, ""));
+    assert_eq!(number::rust_number("0b1010_i8"), int(10, 2, "i8"));
+    assert_eq!(number::rust_number("0x1f32"), int(0x1f32, 16, ""));
+    assert_eq!(
+        number::rust_number("340282366920938463463374607431768211455"),
+        int(u128::MAX, 10, "")
+    );
+}
+
+#[test]
+fn test_rust_floats() {
+    assert_eq!(number::rust_number("1.5"), float(1.5, ""));
+    assert_eq!(number::rust_number("1_0.2_5e-1_0"), float(10.25e-10, ""));
+    assert_eq!(number::rust_number("2E3f32"), float(2e3, "f32"));
+    assert_eq!(number::rust_number("1f64"), float(1.0, "f64"));
+}
+
+#[test]
+fn test_errors() {
+    use NumberErrorKind::*;
+    assert_eq!(number::rust_number("0x"), error(0, 2, NoDigits));
+    assert_eq!(number::rust_number("0x_"), error(0, 3, NoDigits));
+    assert_eq!(number::rust_number("0b1021"), error(4, 1, InvalidDigit));
+    assert_eq!(number::rust_number("0o78"), error(3, 1, InvalidDigit));
+    assert_eq!(number::rust_number("1e+"), error(1, 2, NoDigits));
+    assert_eq!(number::rust_number("12u7"), error(2, 2, InvalidSuffix));
+    assert_eq!(number::rust_number("1.5u8"), error(3, 2, InvalidSuffix));
+    assert_eq!(number::rust_number("256u8"), error(0, 3, Overflow));
+    assert_eq!(number::rust_number("0x80i8"), error(0, 4, Overflow));
+    assert_eq!(number::rust_number("1e39f32"), error(0, 4, Overflow));
+    assert_eq!(number::rust_number("1e309"), error(0, 5, Overflow));
+    assert_eq!(
+        number::rust_number("340282366920938463463374607431768211456_u128"),
+        error(0, 40, Overflow)
+    );
+}
+
+#[test]
+fn test_other_formats() {
+    assert_eq!(number::python_number("1_0j"), float(10.0, "j"));
+    assert_eq!(number::python_number("0o17"), int(15, 8, ""));
+    assert_eq!(number::go_number("3i"), int(3, 10, "i"));
+    assert_eq!(number::go_number("0.5i"), float(0.5, "i"));
+
+    let c = NumberFormat {
+        prefixes: false,
+        separators: false,
+        ..NumberFormat::RUST
+    };
+    assert_eq!(c.parse("0x1"), error(1, 2, NumberErrorKind::InvalidSuffix));
+    assert_eq!(c.parse("1_0"), error(1, 2, NumberErrorKind::InvalidSuffix));
+}
+
+#[test]
+fn test_lexer() {
+    let input = "0x1F 2.5e3 300u8";
+    let expected = [
+        (0, Token::Int(int(31, 16, "")), 4),
+        (5, Token::Float(float(2500.0, "")), 10),
+        (11, Token::Int(error(0, 3, NumberErrorKind::Overflow)), 16),
+    ];
+    itertools::assert_equal(Token::lex(input).map(Result::unwrap), expected);
+}
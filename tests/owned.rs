@@ -0,0 +1,68 @@
+#![cfg(feature = "alloc")]
+
+use std::{rc::Rc, sync::Arc, thread};
+
+use lexi_matic::{LexOwned, Lexer};
+
+#[derive(Debug, Lexer, PartialEq, Eq)]
+#[lexer(owned, skip = r"[ \t\r\n\f]+")]
+enum Token {
+    #[token("import")]
+    Import,
+    #[regex("[a-zA-Z_][a-zA-Z0-9_]*")]
+    Ident(String),
+    #[regex("[0-9]+")]
+    Int(Box<str>),
+    #[regex(r#""[^"]*""#)]
+    Str(Rc<str>),
+}
+
+#[derive(Debug, Lexer, PartialEq, Eq)]
+#[lexer(owned, skip = r"[ \t\r\n\f]+")]
+enum SendToken {
+    #[regex("[a-zA-Z_][a-zA-Z0-9_]*")]
+    Ident(Arc<str>),
+}
+
+#[test]
+fn test_tokens() {
+    let source = String::from(r#"import foo 42 "bar""#);
+    let tokens = Token::lex_owned(source.clone());
+    drop(source);
+    let expected = [
+        (0, Token::Import, 6),
+        (7, Token::Ident("foo".into()), 10),
+        (11, Token::Int("42".into()), 13),
+        (14, Token::Str(r#""bar""#.into()), 19),
+    ];
+    itertools::assert_equal(tokens.map(Result::unwrap), expected);
+}
+
+#[test]
+fn test_same_as_borrowed() {
+    let input = r#"import foo 42 "bar" ?"#;
+    let borrowed: Vec<_> = Token::lex(input)
+        .take(5)
+        .map(|r| r.map_err(|e| e.0))
+        .collect();
+    let owned: Vec<_> = Token::lex_owned(input)
+        .take(5)
+        .map(|r| r.map_err(|e| e.0))
+        .collect();
+    assert_eq!(borrowed, owned);
+}
+
+#[test]
+fn test_send() {
+    let tokens = SendToken::lex_owned("foo bar");
+    let tokens = thread::spawn(move || tokens.map(Result::unwrap).collect::<Vec<_>>())
+        .join()
+        .unwrap();
+    assert_eq!(
+        tokens,
+        [
+            (0, SendToken::Ident("foo".into()), 3),
+            (4, SendToken::Ident("bar".into()), 7),
+        ]
+    );
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "parallel")]
+
+use lexi_matic::{lex_parallel, line_start, Lexer};
+
+#[derive(Debug, Lexer, PartialEq, Eq)]
+#[lexer(skip = "//[^\n]*", skip = r"[ \t\r\n\f]+")]
+enum Token<'a> {
+    #[token("import")]
+    Import,
+    #[token(";")]
+    Semi,
+    #[regex("[a-zA-Z_][a-zA-Z0-9_]*")]
+    Ident(&'a str),
+    #[regex("[0-9]+")]
+    Int(&'a str),
+}
+
+#[test]
+fn test_same_as_sequential() {
+    let mut input = String::new();
+    for i in 0..1000 {
+        input += &format!("import foo{i}; // ✓ {i}\n{i};\n\n");
+    }
+    let sequential: Vec<_> = Token::lex(&input).map(Result::unwrap).collect();
+    for chunks in [1, 2, 3, 8, 100, 10000] {
+        let parallel = lex_parallel::<Token>(&input, chunks, line_start).unwrap();
+        assert_eq!(parallel, sequential, "{chunks}");
+    }
+}
+
+#[test]
+fn test_custom_sync_point() {
+    // Split only at blank lines.
+    let input = "import a;\nimport b;\n\nimport c;\n\nimport d;";
+    let tokens = lex_parallel::<Token>(input, 4, |input, i| input[..i].ends_with("\n\n")).unwrap();
+    let sequential: Vec<_> = Token::lex(input).map(Result::unwrap).collect();
+    assert_eq!(tokens, sequential);
+}
+
+#[test]
+fn test_first_error() {
+    let input = "import a;\nimport ?;\nimport b;\nimport $;\n";
+    let e = lex_parallel::<Token>(input, 4, line_start).unwrap_err();
+    assert_eq!(e.0, 17);
+    assert_eq!(&input[e.0..e.0 + 1], "?");
+}
+
+#[test]
+fn test_line_start() {
+    assert!(!line_start("a\nb", 0));
+    assert!(!line_start("a\nb", 1));
+    assert!(line_start("a\nb", 2));
+    assert!(!line_start("a\nb", 3));
+    assert!(!line_start("a\n", 5));
+}
+
+#[test]
+fn test_empty() {
+    assert!(lex_parallel::<Token>("", 4, line_start).unwrap().is_empty());
+}
+
+#[derive(Debug, Lexer, PartialEq, Eq)]
+#[lexer(skip = r"[ \t\r\n\f]+")]
+enum WithEof<'a> {
+    #[regex("[a-z]+")]
+    Ident(&'a str),
+    #[lexer(eof)]
+    Eof,
+}
+
+#[test]
+fn test_eof_once() {
+    let input = "a\nb\nc\n".repeat(100);
+    let sequential: Vec<_> = WithEof::lex(&input).map(Result::unwrap).collect();
+    let parallel = lex_parallel::<WithEof>(&input, 8, line_start).unwrap();
+    assert_eq!(parallel, sequential);
+    assert_eq!(
+        parallel.last(),
+        Some(&(input.len(), WithEof::Eof, input.len()))
+    );
+}
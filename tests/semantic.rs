@@ -0,0 +1,79 @@
+#![cfg(feature = "dfa")]
+use lexi_matic::{
+    semantic::{semantic_tokens, Semantic},
+    Lexer,
+};
+
+#[derive(Debug, Lexer)]
+#[lexer(
+    skip = r"[ \t\r\n\f]+",
+    skip(r"/\*([^*]|\*[^/])*\*/", semantic = "comment.documentation")
+)]
+enum Token {
+    #[token("fn")]
+    #[lexer(semantic = "keyword")]
+    Fn,
+    #[regex(r#""[^"]*""#)]
+    #[lexer(semantic = "string")]
+    Str,
+    #[regex(r"\p{XID_Start}\p{XID_Continue}*")]
+    #[lexer(semantic = "function.declaration.static")]
+    Ident,
+    #[token("(")]
+    LParen,
+    #[token(")")]
+    RParen,
+}
+
+fn tokens(input: &str) -> Vec<[u32; 5]> {
+    semantic_tokens::<Token>(input).collect()
+}
+
+#[test]
+fn test_legend() {
+    assert_eq!(
+        Token::TOKEN_TYPES,
+        ["keyword", "string", "function", "comment"]
+    );
+    assert_eq!(
+        Token::TOKEN_MODIFIERS,
+        ["declaration", "static", "documentation"]
+    );
+}
+
+#[test]
+fn test_semantic_tokens() {
+    assert_eq!(
+        tokens("fn f() \"x\"\n  fn"),
+        [
+            [0, 0, 2, 0, 0],
+            [0, 3, 1, 2, 0b11],
+            [0, 4, 3, 1, 0],
+            [1, 2, 2, 0, 0],
+        ]
+    );
+}
+
+#[test]
+fn test_multiline() {
+    // Multiline tokens are split per line, and `\r\n` is one line break.
+    assert_eq!(
+        tokens("fn /* a\r\n\r\nbc */ \"x\r\ny\""),
+        [
+            [0, 0, 2, 0, 0],
+            [0, 3, 4, 3, 0b100],
+            [2, 0, 5, 3, 0b100],
+            [0, 6, 2, 1, 0],
+            [1, 0, 2, 1, 0],
+        ]
+    );
+}
+
+#[test]
+fn test_utf16() {
+    // `𝒳` is two UTF-16 code units, `é` one. Errors are skipped.
+    assert_eq!(
+        tokens("\"𝒳\" ? é𝒳 fn"),
+        [[0, 0, 4, 1, 0], [0, 7, 3, 2, 0b11], [0, 4, 2, 0, 0]]
+    );
+}
@@ -0,0 +1,101 @@
+#![cfg(feature = "dfa")]
+use lexi_matic::{Error, ErrorKind, Lexer, Lossless, More};
+
+#[macro_use]
+mod common;
+
+use common::lex;
+
+backends! {
+    #[lexer(
+        skip = r"[ \t]+",
+        skip(r"/\*", more = nested_comment, label = "comment"),
+        skip(r"\\\n", label = "line continuation"),
+        skip(r"#[a-z]*", callback = pragma),
+    )]
+    enum<'a> {
+        #[regex("[a-zA-Z_][a-zA-Z0-9_]*")]
+        Ident(&'a str),
+        #[token("*")]
+        Star,
+        #[token("\n")]
+        Newline,
+    }
+}
+
+fn nested_comment(_: &str, remaining: &str) -> Option<usize> {
+    let mut depth = 1;
+    let mut i = 0;
+    while i < remaining.len() {
+        if remaining[i..].starts_with("/*") {
+            depth += 1;
+            i += 2;
+        } else if remaining[i..].starts_with("*/") {
+            depth -= 1;
+            i += 2;
+            if depth == 0 {
+                return Some(i);
+            }
+        } else {
+            i += 1;
+        }
+    }
+    None
+}
+
+// `#star` is a token, other pragmas are skipped.
+fn pragma<T: From<Star>>(matched: &str, _: &str) -> Result<More<T>, std::convert::Infallible> {
+    Ok(if matched == "#star" {
+        More::Replace(T::from(Star))
+    } else {
+        More::Skip
+    })
+}
+
+struct Star;
+
+macro_rules! from_star {
+    ($($name:ident),*) => {
+        $(impl From<Star> for $name<'_> {
+            fn from(_: Star) -> Self {
+                $name::Star
+            }
+        })*
+    };
+}
+from_star!(Plain, Accelerated, Native);
+
+#[test]
+fn test_skip_callbacks() {
+    let input = "a /* x /* y */ * */ b \\\nc #foo #star *\n";
+    let expected = [
+        (0, Plain::Ident("a"), 1),
+        (20, Plain::Ident("b"), 21),
+        (24, Plain::Ident("c"), 25),
+        (31, Plain::Star, 36),
+        (37, Plain::Star, 38),
+        (38, Plain::Newline, 39),
+    ];
+    itertools::assert_equal(Plain::lex(input).map(Result::unwrap), expected);
+
+    for input in [input, "a /* unterminated /* */", "x\\ y"] {
+        let plain = lex::<Plain>(input);
+        assert_eq!(plain, lex::<Accelerated>(input), "{input:?}");
+        assert_eq!(plain, lex::<Native>(input), "{input:?}");
+    }
+}
+
+#[test]
+fn test_skip_errors() {
+    let mut tokens = Plain::lex("a /* /* */ b");
+    assert_eq!(tokens.next(), Some(Ok((0, Plain::Ident("a"), 1))));
+    assert_eq!(tokens.next(), Some(Err(Error(2, ErrorKind::NoMatch))));
+}
+
+#[test]
+fn test_skip_labels() {
+    assert_eq!(
+        Plain::SKIP_LABELS,
+        [r"[ \t]+", "comment", "line continuation", "#[a-z]*"]
+    );
+}
@@ -0,0 +1,116 @@
+#![cfg(feature = "testing")]
+use std::{env, fs, panic};
+
+use lexi_matic::{
+    more,
+    testing::{
+        assert_snapshot, assert_snapshot_file, check_invariants, check_random, render, BLESS_VAR,
+    },
+    Lexer,
+};
+
+#[derive(Debug, Lexer)]
+#[lexer(skip(r"[ \n]+", label = "space"), skip = "#[^\n]*")]
+enum Token<'a> {
+    #[token("let")]
+    Let,
+    #[token("=")]
+    Eq,
+    #[token("+")]
+    Plus,
+    #[token(";")]
+    Semi,
+    #[regex("[0-9]+")]
+    Num,
+    #[regex("[a-zé]+")]
+    Ident(&'a str),
+}
+
+#[test]
+fn test_render() {
+    assert_eq!(
+        render::<Token>("x+ 1é"),
+        "Ident \"x\" @0..1\nPlus \"+\" @1..2\nNum \"1\" @3..4\nIdent \"é\" @4..6\n",
+    );
+    let Some(Ok((_, Token::Ident(ident), _))) = Token::lex("é").next() else {
+        panic!();
+    };
+    assert_eq!(ident, "é");
+}
+
+#[test]
+fn test_snapshot_file() {
+    assert_snapshot_file::<Token>("tests/golden/expr.txt");
+}
+
+/// The message `assert_snapshot` panics with, `None` if it doesn't.
+fn panic_message(input: &'static str, golden: &str) -> Option<String> {
+    let golden = golden.to_owned();
+    let r = panic::catch_unwind(|| assert_snapshot::<Token>(input, golden));
+    r.err().map(|e| *e.downcast::<String>().unwrap())
+}
+
+#[test]
+fn test_mismatch() {
+    if env::var_os(BLESS_VAR).is_some() {
+        return;
+    }
+    let dir = env!("CARGO_TARGET_TMPDIR");
+    let golden = format!("{dir}/mismatch.tokens");
+    fs::write(
+        &golden,
+        "Let \"let\" @0..3\nIdent \"x\" @4..5\nSemi \";\" @5..6\n",
+    )
+    .unwrap();
+    let message = panic_message("let y;", &golden).unwrap();
+    assert!(message.contains("run with LEXI_MATIC_BLESS=1"), "{message}");
+    assert!(
+        message.ends_with(
+            ":\n Let \"let\" @0..3\n-Ident \"x\" @4..5\n+Ident \"y\" @4..5\n Semi \";\" @5..6\n"
+        ),
+        "{message}"
+    );
+    assert_eq!(panic_message("let x;", &golden), None);
+
+    let missing = format!("{dir}/missing.tokens");
+    let _ = fs::remove_file(&missing);
+    let message = panic_message("let", &missing).unwrap();
+    assert!(message.contains("doesn't exist"), "{message}");
+}
+
+#[derive(Debug, Lexer)]
+#[lexer(skip = r"[ \t\r\n\f]+")]
+enum Raw {
+    #[regex("[a-zA-Z_][a-zA-Z0-9_]*")]
+    Ident,
+    #[regex(r##"b?r#*""##)]
+    #[lexer(more = more::rust_raw_string)]
+    RustRaw,
+}
+
+#[test]
+fn test_check_random() {
+    check_random::<Token>();
+    check_random::<Raw>();
+}
+
+/// Extends `#` tokens to the next byte, which may be inside a char.
+fn next_byte(_: &str, remaining: &str) -> Option<usize> {
+    (!remaining.is_empty()).then_some(1)
+}
+
+#[derive(Debug, Lexer)]
+enum Bad {
+    #[regex("#")]
+    #[lexer(more = next_byte)]
+    Hash,
+    #[regex(".")]
+    Char,
+}
+
+#[test]
+fn test_check_random_fails() {
+    check_invariants::<Bad>("#a");
+    assert!(panic::catch_unwind(|| check_invariants::<Bad>("#é")).is_err());
+    assert!(panic::catch_unwind(check_random::<Bad>).is_err());
+}
@@ -0,0 +1,147 @@
+#![cfg(feature = "alloc")]
+use lexi_matic::{
+    textmate::{sublime_syntax, tm_language, Grammar, GrammarError, GrammarErrorKind},
+    Lexer,
+};
+
+#[derive(Debug, Lexer)]
+#[lexer(
+    skip = r"[ \t\r\n\f]+",
+    skip("//[^\n]*", scope = "comment.line.double-slash", label = "comment")
+)]
+enum Token {
+    #[token("fn")]
+    #[lexer(scope = "keyword.other")]
+    Fn,
+    #[token("+=")]
+    #[lexer(scope = "keyword.operator")]
+    AddAssign,
+    #[regex(r#""([^"\\\n]|\\.)*""#)]
+    #[lexer(scope = "string.quoted.double")]
+    Str,
+    #[regex("[a-zA-Z_][a-zA-Z0-9_]*")]
+    Ident,
+}
+
+#[test]
+fn test_tm_language() {
+    let grammar = tm_language::<Token>("Test", "source.test", &["test"]).unwrap();
+    assert_eq!(
+        grammar,
+        r#"{
+  "name": "Test",
+  "scopeName": "source.test",
+  "fileTypes": ["test"],
+  "patterns": [
+    {
+      "name": "keyword.other",
+      "match": "fn\\b"
+    },
+    {
+      "name": "keyword.operator",
+      "match": "\\+="
+    },
+    {
+      "name": "string.quoted.double",
+      "match": "\"(?:[\\x{0}-\\x{9}\\x{B}-!#-\\[\\]-\\x{10FFFF}]|\\\\[\\x{0}-\\x{9}\\x{B}-\\x{10FFFF}])*\""
+    },
+    {
+      "match": "[A-Z_a-z][0-9A-Z_a-z]*"
+    },
+    {
+      "name": "comment.line.double-slash",
+      "match": "//[\\x{0}-\\x{9}\\x{B}-\\x{10FFFF}]*"
+    }
+  ]
+}
+"#
+    );
+}
+
+#[test]
+fn test_sublime_syntax() {
+    let syntax = sublime_syntax::<Token>("Test", "source.test", &["test"]).unwrap();
+    assert!(syntax.starts_with(
+        "%YAML 1.2\n---\nname: 'Test'\nscope: 'source.test'\nfile_extensions: ['test']\ncontexts:\n  main:\n    - match: 'fn\\b'\n      scope: 'keyword.other'\n"
+    ));
+}
+
+#[derive(Debug, Lexer)]
+#[lexer(skip(r"/\*([^*]|\*[^/])*\*/", scope = "comment.block"))]
+enum MultiLine {
+    #[token("a")]
+    A,
+}
+
+#[derive(Debug, Lexer)]
+enum WithCallback {
+    #[regex(r##"r#*""##)]
+    #[lexer(more = lexi_matic::more::rust_raw_string, scope = "string.quoted")]
+    RawStr,
+}
+
+#[derive(Debug, Lexer)]
+enum Unsupported {
+    #[regex(r"(?-u:\b)a")]
+    #[lexer(scope = "keyword.other")]
+    Ascii,
+}
+
+#[test]
+fn test_errors() {
+    let error = |pattern, kind| Err(GrammarError { pattern, kind });
+    assert_eq!(
+        tm_language::<MultiLine>("", "", &[]),
+        error(r"/\*([^*]|\*[^/])*\*/", GrammarErrorKind::SpansLines),
+    );
+    assert_eq!(
+        tm_language::<WithCallback>("", "", &[]),
+        error("RawStr", GrammarErrorKind::Callback),
+    );
+    assert_eq!(
+        tm_language::<Unsupported>("", "", &[]),
+        error(
+            "Ascii",
+            GrammarErrorKind::Unsupported("unsupported assertion")
+        ),
+    );
+    assert_eq!(
+        tm_language::<Unsupported>("", "", &[])
+            .unwrap_err()
+            .to_string(),
+        "pattern `Ascii` uses an unsupported regex feature: unsupported assertion"
+    );
+}
+
+#[derive(Debug, Lexer)]
+#[lexer(
+    skip(r"/\*([^*]|\*[^/])*\*/", label = "block"),
+    skip(r"--\[=*\[", more = lexi_matic::more::lua_long_bracket, label = "long")
+)]
+enum Unscoped {
+    #[token("a")]
+    #[lexer(scope = "keyword.other")]
+    A,
+}
+
+#[test]
+fn test_unscoped() {
+    // Unscoped patterns with line breaks or callbacks are left out, like the
+    // whitespace of `Token`.
+    assert!(Token::PATTERNS[4].spans_lines);
+    assert_eq!(
+        tm_language::<Unscoped>("", "", &[]).unwrap(),
+        r#"{
+  "name": "",
+  "scopeName": "",
+  "fileTypes": [],
+  "patterns": [
+    {
+      "name": "keyword.other",
+      "match": "a\\b"
+    }
+  ]
+}
+"#
+    );
+}
@@ -0,0 +1,86 @@
+#![cfg(feature = "dfa")]
+use lexi_matic::{Error, ErrorKind, Lexer, Lossless, More};
+
+#[derive(Debug, Lexer, PartialEq, Eq)]
+#[lexer(skip(r"[ \n]+", label = "space"), skip = "#[^\n]*")]
+enum Token<'a> {
+    #[regex("[a-zé]+")]
+    #[lexer(callback = ident)]
+    Ident(&'a str),
+}
+
+fn ident<'a>(matched: &str, _: &str) -> Result<More<Token<'a>>, std::convert::Infallible> {
+    Ok(if matched == "skip" {
+        More::Skip
+    } else {
+        More::Extend(0)
+    })
+}
+
+#[test]
+fn test_trace() {
+    let mut trace = String::new();
+    let tokens: Vec<_> = Token::trace("é ? skip # c\n\"a", &mut trace).collect();
+    assert_eq!(
+        tokens,
+        [
+            Ok((0, Token::Ident("é"), 2)),
+            Err(Error(3, ErrorKind::NoMatch)),
+            Err(Error(14, ErrorKind::NoMatch)),
+            Ok((15, Token::Ident("a"), 16)),
+        ]
+    );
+    let expected = r##"1:1 0..2 Ident("é") "é"
+1:2 2..3 skip space " "
+1:3 3..4 error NoMatch "?"
+1:4 4..5 skip space " "
+1:5 5..9 skip "skip"
+1:9 9..10 skip space " "
+1:10 10..13 skip #[^\n]* "# c"
+1:13 13..14 skip space "\n"
+2:1 14..15 error NoMatch "\""
+2:2 15..16 Ident("a") "a"
+"##;
+    assert_eq!(trace, expected);
+}
+
+#[cfg(feature = "log")]
+mod with_log {
+    use std::sync::Mutex;
+
+    use lexi_matic::{trace::Log, Lossless};
+
+    use super::Token;
+
+    static LINES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    struct Logger;
+
+    impl log::Log for Logger {
+        fn enabled(&self, _: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            assert_eq!(record.target(), "lexi_matic");
+            LINES.lock().unwrap().push(record.args().to_string());
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn test_log() {
+        log::set_logger(&Logger).unwrap();
+        log::set_max_level(log::LevelFilter::Trace);
+        assert_eq!(Token::trace("a b", Log::default()).count(), 2);
+        assert_eq!(
+            *LINES.lock().unwrap(),
+            [
+                r#"1:1 0..1 Ident("a") "a""#,
+                r#"1:2 1..2 skip space " ""#,
+                r#"1:3 2..3 Ident("b") "b""#,
+            ]
+        );
+    }
+}
@@ -0,0 +1,11 @@
+use lexi_matic::Lexer;
+
+#[derive(Debug, Lexer)]
+enum Token {
+    #[regex("[a-z]+")]
+    Ident,
+}
+
+fn main() {
+    let _ = Token::lex("a");
+}
@@ -0,0 +1,12 @@
+use lexi_matic::{lex_parallel, line_start, Lexer, Symbol};
+
+#[derive(Debug, Lexer)]
+#[lexer(codegen = "native", skip = r"[ \n]+")]
+enum Token {
+    #[regex("[a-z]+")]
+    Ident(#[lexer(intern)] Symbol),
+}
+
+fn main() {
+    let _ = lex_parallel::<Token>("foo\nbar", 2, line_start);
+}
@@ -0,0 +1,12 @@
+use lexi_matic::Lexer;
+
+#[derive(Debug, Lexer)]
+enum Token<'a> {
+    #[regex("[a-z]+")]
+    Ident {
+        #[lexer(text, span)]
+        name: &'a str,
+    },
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use lexi_matic::{textmate::Grammar, Lexer};
+
+// Without scopes there is no grammar.
+#[derive(Debug, Lexer)]
+enum Token {
+    #[token("a")]
+    A,
+}
+
+fn main() {
+    let _ = <Token as Grammar>::PATTERNS;
+}
@@ -0,0 +1,10 @@
+use lexi_matic::Lexer;
+
+#[derive(Debug, Lexer)]
+#[lexer(owned)]
+enum Token<'src> {
+    #[regex("[a-z]+")]
+    Ident(&'src str),
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use std::ops::Range;
+
+use lexi_matic::Lexer;
+
+#[derive(Debug, Lexer)]
+enum Token<'a> {
+    #[regex("[a-z]+")]
+    Ident(&'a str, Range<usize>),
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use lexi_matic::Lexer;
+
+#[derive(Debug, Lexer)]
+struct Token<T> {
+    value: T,
+}
+
+fn main() {}
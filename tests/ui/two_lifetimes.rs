@@ -0,0 +1,11 @@
+use lexi_matic::Lexer;
+
+#[derive(Debug, Lexer)]
+enum Token<'a, 'b> {
+    #[regex("[a-z]+")]
+    Ident(&'a str),
+    #[regex("[0-9]+")]
+    Num(&'b str),
+}
+
+fn main() {}